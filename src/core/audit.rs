@@ -0,0 +1,397 @@
+use std::{
+	fs,
+	net::{IpAddr, Ipv6Addr},
+	path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Result};
+use toml::{Table, Value};
+
+use super::{item::Item, selection::HostKey};
+#[cfg(not(test))]
+use crate::util::get_config_dir;
+
+const BUNDLED_RULES: &str = include_str!("../../assets/audit_rules.toml");
+
+/// Imported rules, used instead of the bundled ones unless those are newer.
+const RULES_FILE_NAME: &str = "audit_rules.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleKind {
+	/// A domain mapped to a public ip other than loopback.
+	Redirect,
+	/// A domain mapped to loopback or an unspecified address.
+	Blocked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+	Low,
+	Medium,
+	High,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRule {
+	pub id: String,
+	pub kind: RuleKind,
+	pub severity: Severity,
+	pub name: String,
+	/// Also matching their subdomains.
+	pub domains: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRules {
+	pub version: String,
+	pub rules: Vec<AuditRule>,
+	/// Whether the rules were imported rather than bundled.
+	pub imported: bool,
+}
+
+/// An enabled host of the draft matched by a rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+	pub key: HostKey,
+	pub ip: String,
+	pub host: String,
+	pub kind: RuleKind,
+	pub severity: Severity,
+	pub rule_name: String,
+}
+
+impl AuditRules {
+	pub fn bundled() -> Self {
+		Self::parse(BUNDLED_RULES).expect("bundled audit rules are valid")
+	}
+
+	/// The imported rules if there are any, the bundled ones otherwise or
+	/// when an update of the app brought newer ones.
+	pub fn load() -> Result<Self> {
+		Self::load_from(&imported_rules_path()?)
+	}
+
+	/// Like `load`, with the imported rules kept at `path`.
+	pub fn load_from(path: &Path) -> Result<Self> {
+		if !path.exists() {
+			return Ok(Self::bundled());
+		}
+		let mut rules = Self::parse(&fs::read_to_string(path)?)?;
+		rules.imported = true;
+
+		Ok(rules.or_newer(Self::bundled()))
+	}
+
+	/// These rules, unless `other` has a newer version.
+	fn or_newer(self, other: Self) -> Self {
+		if version_key(&other.version) > version_key(&self.version) {
+			other
+		} else {
+			self
+		}
+	}
+
+	/// Check the rules file at `path` and keep a copy of it, so that the
+	/// rules can be updated without a new release.
+	pub fn import(path: &Path) -> Result<Self> {
+		Self::import_to(path, &imported_rules_path()?)
+	}
+
+	/// Like `import`, with the copy kept at `imported_path`.
+	pub fn import_to(path: &Path, imported_path: &Path) -> Result<Self> {
+		let content = fs::read_to_string(path)?;
+		let mut rules = Self::parse(&content)?;
+		let bundled = Self::bundled();
+		if version_key(&rules.version) < version_key(&bundled.version) {
+			bail!(
+				"version `{}` is older than the bundled `{}`",
+				rules.version,
+				bundled.version
+			);
+		}
+		if let Some(dir) = imported_path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		fs::write(imported_path, content)?;
+		rules.imported = true;
+
+		Ok(rules)
+	}
+
+	pub fn parse(content: &str) -> Result<Self> {
+		let table = content.parse::<Table>()?;
+		let version = table
+			.get("version")
+			.and_then(Value::as_str)
+			.ok_or_else(|| anyhow!("missing `version`"))?
+			.to_string();
+		let rules = table
+			.get("rule")
+			.and_then(Value::as_array)
+			.map(|rules| rules.iter().map(parse_rule).collect())
+			.unwrap_or_else(|| Ok(vec![]))?;
+
+		Ok(Self {
+			version,
+			rules,
+			imported: false,
+		})
+	}
+
+	pub fn audit(&self, list: &[Item]) -> Vec<Finding> {
+		let mut findings = vec![];
+
+		for item in list {
+			let Ok(ip) = item.ip.parse::<IpAddr>() else {
+				continue;
+			};
+			let kind = if is_blocking(&ip) {
+				RuleKind::Blocked
+			} else if is_public(&ip) {
+				RuleKind::Redirect
+			} else {
+				continue;
+			};
+			for host in item.hosts.iter().filter(|host| host.enabled) {
+				let Some(rule) = self
+					.rules
+					.iter()
+					.find(|rule| rule.kind == kind && rule.matches(&host.name))
+				else {
+					continue;
+				};
+				findings.push(Finding {
+					key: (item.id, host.id),
+					ip: item.ip.clone(),
+					host: host.name.clone(),
+					kind,
+					severity: rule.severity,
+					rule_name: rule.name.clone(),
+				});
+			}
+		}
+
+		findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity));
+		findings
+	}
+}
+
+impl AuditRule {
+	fn matches(&self, host: &str) -> bool {
+		let host = host.trim_end_matches('.').to_lowercase();
+		self.domains.iter().any(|domain| {
+			host == *domain
+				|| host
+					.strip_suffix(domain.as_str())
+					.is_some_and(|sub| sub.ends_with('.'))
+		})
+	}
+}
+
+fn parse_rule(value: &Value) -> Result<AuditRule> {
+	let get_str = |key: &str| {
+		value
+			.get(key)
+			.and_then(Value::as_str)
+			.ok_or_else(|| anyhow!("missing `{}` of a rule", key))
+	};
+	let id = get_str("id")?.to_string();
+	let kind = match get_str("kind")? {
+		"redirect" => RuleKind::Redirect,
+		"blocked" => RuleKind::Blocked,
+		kind => {
+			return Err(anyhow!("unknown kind `{}` of rule `{}`", kind, id))
+		}
+	};
+	let severity = match get_str("severity")? {
+		"low" => Severity::Low,
+		"medium" => Severity::Medium,
+		"high" => Severity::High,
+		severity => {
+			return Err(anyhow!(
+				"unknown severity `{}` of rule `{}`",
+				severity,
+				id
+			))
+		}
+	};
+	let domains = value
+		.get("domains")
+		.and_then(Value::as_array)
+		.ok_or_else(|| anyhow!("missing `domains` of rule `{}`", id))?
+		.iter()
+		.filter_map(Value::as_str)
+		.map(str::to_lowercase)
+		.collect();
+
+	Ok(AuditRule {
+		name: get_str("name")?.to_string(),
+		id,
+		kind,
+		severity,
+		domains,
+	})
+}
+
+fn is_blocking(ip: &IpAddr) -> bool {
+	ip.is_loopback() || ip.is_unspecified()
+}
+
+fn is_public(ip: &IpAddr) -> bool {
+	match ip {
+		IpAddr::V4(ip) => {
+			!(ip.is_private()
+				|| ip.is_loopback()
+				|| ip.is_link_local()
+				|| ip.is_unspecified()
+				|| ip.is_broadcast())
+		}
+		IpAddr::V6(ip) => {
+			!(ip.is_loopback() || ip.is_unspecified() || is_local_v6(ip))
+		}
+	}
+}
+
+/// Unique local `fc00::/7` and link local `fe80::/10` addresses.
+fn is_local_v6(ip: &Ipv6Addr) -> bool {
+	let first = ip.segments()[0];
+	first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80
+}
+
+/// Versions like `2024.09.1` compare part by part, numbers by value.
+fn version_key(version: &str) -> Vec<(u64, &str)> {
+	version
+		.split('.')
+		.map(|part| match part.parse() {
+			Ok(number) => (number, ""),
+			Err(_) => (0, part),
+		})
+		.collect()
+}
+
+#[cfg(not(test))]
+fn imported_rules_path() -> Result<PathBuf> {
+	Ok(get_config_dir()?.join(RULES_FILE_NAME))
+}
+
+/// Tests never read or replace the rules imported by the user.
+#[cfg(test)]
+fn imported_rules_path() -> Result<PathBuf> {
+	let dir = std::env::temp_dir().join("hed_test_audit");
+	Ok(dir
+		.join(std::process::id().to_string())
+		.join(RULES_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{env, fs};
+
+	use rstest::rstest;
+
+	use super::{version_key, AuditRules, RuleKind, Severity};
+	use crate::core::Item;
+
+	#[test]
+	fn test_bundled_rules() {
+		let rules = AuditRules::bundled();
+
+		assert!(!rules.version.is_empty());
+		assert!(!rules.rules.is_empty());
+	}
+
+	#[rstest]
+	#[case("203.0.113.7", "www.paypal.com", Some(RuleKind::Redirect))]
+	#[case("2001:db8::1", "PayPal.com", Some(RuleKind::Redirect))]
+	#[case("192.168.1.2", "paypal.com", None)]
+	#[case("127.0.0.1", "paypal.com", None)]
+	#[case("203.0.113.7", "notpaypal.com", None)]
+	#[case("0.0.0.0", "update.microsoft.com", Some(RuleKind::Blocked))]
+	#[case("::1", "mesu.apple.com", Some(RuleKind::Blocked))]
+	#[case("203.0.113.7", "kaspersky.com", None)]
+	fn test_audit(
+		#[case] ip: &str,
+		#[case] host: &str,
+		#[case] expected: Option<RuleKind>,
+	) {
+		let rules = AuditRules::bundled();
+		let list = vec![Item::new(ip, vec![host.to_string()], true)];
+
+		let findings = rules.audit(&list);
+
+		assert_eq!(findings.first().map(|f| f.kind), expected);
+	}
+
+	#[test]
+	fn test_audit_skips_disabled() {
+		let rules = AuditRules::bundled();
+		let list =
+			vec![Item::new("0.0.0.0", vec!["avast.com".to_string()], false)];
+
+		assert!(rules.audit(&list).is_empty());
+	}
+
+	#[rstest]
+	#[case("2024.09.1", "2024.10.1")]
+	#[case("2024.09.1", "2024.09.10")]
+	#[case("2024.9", "2024.09.1")]
+	#[case("1", "2")]
+	fn test_version_order(#[case] older: &str, #[case] newer: &str) {
+		assert!(version_key(older) < version_key(newer));
+	}
+
+	#[test]
+	fn test_newer_rules_win() {
+		let bundled = AuditRules::bundled();
+		let rules = |version: &str| AuditRules {
+			version: version.to_string(),
+			rules: vec![],
+			imported: true,
+		};
+
+		assert!(rules("2000.01.1").or_newer(bundled.clone()) == bundled);
+		assert!(rules("9999.01.1").or_newer(bundled.clone()).imported);
+	}
+
+	#[test]
+	fn test_import_and_load() {
+		let dir = env::temp_dir().join("hed_test_import_rules");
+		fs::create_dir_all(&dir).unwrap();
+		let imported = dir.join("imported.toml");
+		let file = |version: &str| {
+			let path = dir.join(format!("{version}.toml"));
+			fs::write(&path, format!("version = \"{version}\"\n")).unwrap();
+			path
+		};
+
+		assert!(!AuditRules::load_from(&imported).unwrap().imported);
+		assert!(AuditRules::import_to(&file("2000.01.1"), &imported).is_err());
+		assert!(!imported.exists());
+
+		let rules = AuditRules::import_to(&file("9999.01.1"), &imported);
+		assert!(rules.unwrap().imported);
+		let rules = AuditRules::load_from(&imported).unwrap();
+		assert!(rules.imported);
+		assert_eq!(rules.version, "9999.01.1");
+
+		// an update brought newer rules than the imported ones
+		fs::write(&imported, "version = \"2000.01.1\"\n").unwrap();
+		assert!(!AuditRules::load_from(&imported).unwrap().imported);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_parse_rules() {
+		let rules = AuditRules::parse(
+			"version = \"1\"\n[[rule]]\nid = \"a\"\nkind = \"blocked\"\n\
+			 severity = \"low\"\nname = \"A\"\ndomains = [\"A.test\"]\n",
+		)
+		.unwrap();
+
+		assert_eq!(rules.rules[0].severity, Severity::Low);
+		assert_eq!(rules.rules[0].domains, ["a.test"]);
+		assert!(AuditRules::parse("version = \"1\"\n[[rule]]\nid = \"a\"\n")
+			.is_err());
+	}
+}
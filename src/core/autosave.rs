@@ -0,0 +1,110 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use toml::{Table, Value};
+
+use crate::util::{get_data_dir, now_secs};
+
+const AUTOSAVE_FILE_NAME: &str = "draft.toml";
+
+/// The unsaved draft of a hosts file, kept on disk so that a crash doesn't
+/// lose it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Autosave {
+	pub hosts_path: PathBuf,
+	pub content: String,
+	/// Unix seconds.
+	pub saved_at: u64,
+}
+
+impl Autosave {
+	pub fn new(hosts_path: PathBuf, content: String) -> Self {
+		Self {
+			hosts_path,
+			content,
+			saved_at: now_secs(),
+		}
+	}
+
+	pub fn load() -> Option<Self> {
+		autosave_path()
+			.and_then(|path| Ok(fs::read_to_string(path)?))
+			.and_then(|content| Ok(content.parse::<Table>()?))
+			.ok()
+			.and_then(|table| Self::from_table(&table))
+	}
+
+	/// Written to a temporary file first, so that a crash while saving
+	/// keeps the previous draft.
+	pub fn save(&self) -> Result<()> {
+		let path = autosave_path()?;
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		let tmp_path = path.with_extension("tmp");
+		fs::write(&tmp_path, self.to_table().to_string())?;
+		fs::rename(tmp_path, path)?;
+
+		Ok(())
+	}
+
+	pub fn remove() -> Result<()> {
+		let path = autosave_path()?;
+		if path.exists() {
+			fs::remove_file(path)?;
+		}
+
+		Ok(())
+	}
+
+	fn from_table(table: &Table) -> Option<Self> {
+		Some(Self {
+			hosts_path: PathBuf::from(table.get("hosts_path")?.as_str()?),
+			content: table.get("content")?.as_str()?.to_string(),
+			saved_at: table.get("saved_at")?.as_integer()?.try_into().ok()?,
+		})
+	}
+
+	fn to_table(&self) -> Table {
+		let mut table = Table::new();
+		table.insert(
+			"hosts_path".to_string(),
+			Value::String(self.hosts_path.to_string_lossy().into_owned()),
+		);
+		table.insert(
+			"saved_at".to_string(),
+			Value::Integer(self.saved_at.try_into().unwrap_or(i64::MAX)),
+		);
+		table
+			.insert("content".to_string(), Value::String(self.content.clone()));
+
+		table
+	}
+}
+
+fn autosave_path() -> Result<PathBuf> {
+	Ok(get_data_dir()?.join(AUTOSAVE_FILE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::PathBuf;
+
+	use toml::Table;
+
+	use super::Autosave;
+
+	#[test]
+	fn test_autosave_round_trip() {
+		let autosave = Autosave {
+			hosts_path: PathBuf::from("/etc/hosts"),
+			content: "# \"quoted\"\r\n127.0.0.1 a.test\n\n".to_string(),
+			saved_at: 1_700_000_000,
+		};
+
+		let content = autosave.to_table().to_string();
+		let table = content.parse::<Table>().unwrap();
+
+		assert_eq!(Autosave::from_table(&table), Some(autosave));
+	}
+}
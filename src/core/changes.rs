@@ -0,0 +1,213 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::item::{Host, Item};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostChange {
+	Added,
+	/// Holds the name in the saved file.
+	Renamed(String),
+	Toggled,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemovedHost {
+	pub name: String,
+	pub enabled: bool,
+}
+
+/// Differences of a draft from the saved hosts file, per item and per host.
+#[derive(Debug, Default)]
+pub struct Changes {
+	hosts: HashMap<(String, String), HostChange>,
+	/// Hosts gone from an ip that is still in the draft.
+	removed: HashMap<String, Vec<RemovedHost>>,
+	/// Items whose ip is gone from the draft, in saved order.
+	removed_items: Vec<(String, Vec<RemovedHost>)>,
+}
+
+impl Changes {
+	/// A host added next to a removed one of the same ip is taken as a
+	/// rename of it.
+	pub fn new(saved: &[Item], draft: &[Item]) -> Self {
+		let mut changes = Self::default();
+		let saved_items: HashMap<&str, &Item> = saved
+			.iter()
+			.rev()
+			.map(|item| (item.ip.as_str(), item))
+			.collect();
+		let mut draft_names: HashMap<&str, HashSet<&str>> = HashMap::new();
+		for item in draft {
+			draft_names
+				.entry(&item.ip)
+				.or_default()
+				.extend(item.hosts.iter().map(|host| host.name.as_str()));
+		}
+		let mut removed: HashMap<&str, VecDeque<&Host>> = saved_items
+			.iter()
+			.filter_map(|(ip, item)| {
+				let names = draft_names.get(ip)?;
+				let hosts = item
+					.hosts
+					.iter()
+					.filter(|host| !names.contains(host.name.as_str()))
+					.collect();
+				Some((*ip, hosts))
+			})
+			.collect();
+
+		for item in draft {
+			let Some(saved_item) = saved_items.get(item.ip.as_str()) else {
+				for host in &item.hosts {
+					changes.insert(&item.ip, &host.name, HostChange::Added);
+				}
+				continue;
+			};
+			let saved_hosts: HashMap<&str, bool> = saved_item
+				.hosts
+				.iter()
+				.map(|host| (host.name.as_str(), host.enabled))
+				.collect();
+			let removed = removed.entry(&item.ip).or_default();
+
+			for host in &item.hosts {
+				let change = match saved_hosts.get(host.name.as_str()) {
+					Some(&enabled) if enabled == host.enabled => continue,
+					Some(_) => HostChange::Toggled,
+					None => match removed.pop_front() {
+						Some(from) => HostChange::Renamed(from.name.clone()),
+						None => HostChange::Added,
+					},
+				};
+				changes.insert(&item.ip, &host.name, change);
+			}
+		}
+
+		for (ip, hosts) in removed {
+			if !hosts.is_empty() {
+				changes.removed.insert(
+					ip.to_string(),
+					hosts.into_iter().map(removed_host).collect(),
+				);
+			}
+		}
+
+		for item in saved {
+			if !draft_names.contains_key(item.ip.as_str()) {
+				changes.removed_items.push((
+					item.ip.clone(),
+					item.hosts.iter().map(removed_host).collect(),
+				));
+			}
+		}
+
+		changes
+	}
+
+	fn insert(&mut self, ip: &str, name: &str, change: HostChange) {
+		self.hosts
+			.entry((ip.to_string(), name.to_string()))
+			.or_insert(change);
+	}
+
+	pub fn host(&self, ip: &str, name: &str) -> Option<&HostChange> {
+		self.hosts.get(&(ip.to_string(), name.to_string()))
+	}
+
+	pub fn removed(&self, ip: &str) -> &[RemovedHost] {
+		self.removed.get(ip).map_or(&[], Vec::as_slice)
+	}
+
+	pub fn removed_items(&self) -> &[(String, Vec<RemovedHost>)] {
+		&self.removed_items
+	}
+}
+
+fn removed_host(host: &Host) -> RemovedHost {
+	RemovedHost {
+		name: host.name.clone(),
+		enabled: host.enabled,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{Changes, HostChange, RemovedHost};
+	use crate::core::Item;
+
+	fn item(ip: &str, hosts: &[(&str, bool)]) -> Item {
+		let mut item = Item::new(ip, vec![], true);
+		for (name, enabled) in hosts {
+			item.add_hosts(vec![name.to_string()], *enabled);
+		}
+		item
+	}
+
+	#[test]
+	fn test_changes() {
+		let saved = vec![
+			item("127.0.0.1", &[("a.test", true), ("b.test", true)]),
+			item("127.0.0.2", &[("c.test", true), ("d.test", false)]),
+			item("127.0.0.3", &[("e.test", false)]),
+		];
+		let draft = vec![
+			item("127.0.0.1", &[("a.test", false), ("x.test", true)]),
+			item("127.0.0.2", &[("c.test", true), ("y.test", true)]),
+			item("127.0.0.2", &[("z.test", true)]),
+			item("127.0.0.4", &[("f.test", true)]),
+		];
+
+		let changes = Changes::new(&saved, &draft);
+
+		assert_eq!(
+			changes.host("127.0.0.1", "a.test"),
+			Some(&HostChange::Toggled)
+		);
+		assert_eq!(
+			changes.host("127.0.0.1", "x.test"),
+			Some(&HostChange::Renamed("b.test".into()))
+		);
+		assert_eq!(changes.host("127.0.0.2", "c.test"), None);
+		assert_eq!(
+			changes.host("127.0.0.2", "y.test"),
+			Some(&HostChange::Renamed("d.test".into()))
+		);
+		assert_eq!(
+			changes.host("127.0.0.2", "z.test"),
+			Some(&HostChange::Added)
+		);
+		assert_eq!(
+			changes.host("127.0.0.4", "f.test"),
+			Some(&HostChange::Added)
+		);
+		assert!(changes.removed("127.0.0.1").is_empty());
+		assert_eq!(
+			changes.removed_items(),
+			&[(
+				"127.0.0.3".to_string(),
+				vec![RemovedHost {
+					name: "e.test".into(),
+					enabled: false,
+				}]
+			)]
+		);
+	}
+
+	#[test]
+	fn test_removed_hosts() {
+		let saved =
+			vec![item("127.0.0.1", &[("a.test", true), ("b.test", false)])];
+		let draft = vec![item("127.0.0.1", &[("a.test", true)])];
+
+		let changes = Changes::new(&saved, &draft);
+
+		assert_eq!(
+			changes.removed("127.0.0.1"),
+			&[RemovedHost {
+				name: "b.test".into(),
+				enabled: false,
+			}]
+		);
+		assert!(changes.removed_items().is_empty());
+	}
+}
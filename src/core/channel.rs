@@ -1,22 +1,71 @@
-use std::sync::mpsc;
+use std::{path::PathBuf, sync::mpsc};
 
-use super::HostsInfo;
+use egui::Context;
 
+use super::{
+	changes::RemovedHost,
+	find_replace::Replacement,
+	fonts::LoadedFonts,
+	host_timer::TimerTarget,
+	hosts_error::HostsError,
+	lint::Lint,
+	selection::{HostKey, SelectMode},
+	HostsInfo,
+};
+
+/// Results of worker threads carry the path of the tab they belong to, the
+/// user may have switched tabs since.
 pub enum Event {
-	ParseHostsOk(HostsInfo),
-	OsErr(String),
+	ParseHostsOk(PathBuf, HostsInfo),
+	HostsErr(PathBuf, HostsError),
 	EditItemIp(usize, String),
 	ToggleHostEnable(usize, usize),
 	DeleteItem(usize),
 	DeleteHost(usize, usize),
+	RenameHost(usize, usize, String),
+	RestoreHosts(String, Vec<RemovedHost>),
+	FixLint(Lint),
+	ApplyReplacements(Vec<Replacement>),
 	OpenAddHostsWindow(usize),
 	OpenEditHostWindow(usize, usize),
-	SaveHostsOk,
+	SaveHostsOk(PathBuf),
+	/// The path of the tab, and the one it was saved to.
+	SaveAsOk(PathBuf, PathBuf),
 	ToggleAllHostEnable(usize, bool),
+	SelectHost(HostKey, SelectMode),
+	ToggleHostsEnable(Vec<HostKey>, bool),
+	DeleteHosts(Vec<HostKey>),
+	MoveHosts(Vec<HostKey>, String),
+	CopyHosts(Vec<HostKey>, String),
+	MoveItem(usize, usize),
+	MoveHost(HostKey, usize, usize),
+	OpenTimerWindow(TimerTarget),
+	CancelTimer(HostKey),
+	/// The file with the expired timers reverted, as it was saved.
+	ExpiredTimersSaved(PathBuf, HostsInfo),
+	FontsLoaded(LoadedFonts),
+}
+
+/// Sends events to the UI thread and wakes it up to handle them, so that
+/// results of worker threads show without waiting for user input.
+#[derive(Clone)]
+pub struct EventSender {
+	tx: mpsc::Sender<Event>,
+	ctx: Option<Context>,
+}
+
+impl EventSender {
+	pub fn send(&self, event: Event) {
+		// the receiver is only gone when the app is closing
+		let _ = self.tx.send(event);
+		if let Some(ctx) = &self.ctx {
+			ctx.request_repaint();
+		}
+	}
 }
 
 pub struct Channel {
-	pub tx: mpsc::Sender<Event>,
+	pub tx: EventSender,
 	rx: mpsc::Receiver<Event>,
 }
 
@@ -24,13 +73,21 @@ impl Default for Channel {
 	fn default() -> Self {
 		let (tx, rx) = mpsc::channel();
 
-		Self { tx, rx }
+		Self {
+			tx: EventSender { tx, ctx: None },
+			rx,
+		}
 	}
 }
 
 impl Channel {
+	/// Repaint `ctx` on every event, set it before cloning `tx`.
+	pub fn set_ctx(&mut self, ctx: Context) {
+		self.tx.ctx = Some(ctx);
+	}
+
 	pub fn send(&self, event: Event) {
-		self.tx.send(event).unwrap();
+		self.tx.send(event);
 	}
 
 	pub fn recv(&self) -> Option<Event> {
@@ -0,0 +1,133 @@
+use super::{hed::ViewKind, selection::HostKey};
+use crate::{i18n::t, util::fuzzy_match};
+
+/// Maximum number of entries shown by the command palette.
+const MAX_RESULTS: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+	Save,
+	Reset,
+	Reload,
+	OpenFile,
+	SaveAs,
+	CloseTab,
+	Format,
+	Normalize,
+	NewItem,
+	FindReplace,
+	OpenSettings,
+	OpenCommandPalette,
+	Audit,
+	SwitchView(ViewKind),
+	ToggleHost(HostKey),
+	JumpToItem(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct CommandEntry {
+	pub command: Command,
+	pub label: String,
+}
+
+#[derive(Default)]
+pub struct CommandPalette {
+	pub query: String,
+	pub selected: usize,
+}
+
+impl Command {
+	/// Commands that take no argument, which can be bound to a shortcut.
+	pub const BINDABLE: [Command; 15] = [
+		Command::Save,
+		Command::Reset,
+		Command::Reload,
+		Command::OpenFile,
+		Command::SaveAs,
+		Command::CloseTab,
+		Command::Format,
+		Command::Normalize,
+		Command::NewItem,
+		Command::FindReplace,
+		Command::OpenSettings,
+		Command::OpenCommandPalette,
+		Command::Audit,
+		Command::SwitchView(ViewKind::Options),
+		Command::SwitchView(ViewKind::Text),
+	];
+
+	pub fn label(&self) -> &'static str {
+		t(match self {
+			Command::Save => "command.save",
+			Command::Reset => "command.reset",
+			Command::Reload => "command.reload",
+			Command::OpenFile => "command.open_file",
+			Command::SaveAs => "command.save_as",
+			Command::CloseTab => "command.close_tab",
+			Command::Format => "command.format",
+			Command::Normalize => "command.normalize",
+			Command::NewItem => "command.new_item",
+			Command::FindReplace => "command.find_replace",
+			Command::OpenSettings => "command.settings",
+			Command::OpenCommandPalette => "command.command_palette",
+			Command::Audit => "command.audit",
+			Command::SwitchView(ViewKind::Options) => "command.options_view",
+			Command::SwitchView(ViewKind::Text) => "command.text_view",
+			Command::ToggleHost(_) => "command.toggle_host",
+			Command::JumpToItem(_) => "command.jump_to_item",
+		})
+	}
+
+	/// Stable name used in the settings file.
+	pub fn id(&self) -> &'static str {
+		match self {
+			Command::Save => "save",
+			Command::Reset => "reset",
+			Command::Reload => "reload",
+			Command::OpenFile => "open_file",
+			Command::SaveAs => "save_as",
+			Command::CloseTab => "close_tab",
+			Command::Format => "format",
+			Command::Normalize => "normalize",
+			Command::NewItem => "new_item",
+			Command::FindReplace => "find_replace",
+			Command::OpenSettings => "settings",
+			Command::OpenCommandPalette => "command_palette",
+			Command::Audit => "audit",
+			Command::SwitchView(ViewKind::Options) => "options_view",
+			Command::SwitchView(ViewKind::Text) => "text_view",
+			Command::ToggleHost(_) => "toggle_host",
+			Command::JumpToItem(_) => "jump_to_item",
+		}
+	}
+}
+
+impl CommandEntry {
+	pub fn new(command: Command, label: impl Into<String>) -> Self {
+		Self {
+			command,
+			label: label.into(),
+		}
+	}
+}
+
+impl CommandPalette {
+	pub fn reset(&mut self) {
+		self.query.clear();
+		self.selected = 0;
+	}
+
+	pub fn filter(&self, entries: Vec<CommandEntry>) -> Vec<CommandEntry> {
+		let mut matched: Vec<(usize, CommandEntry)> = entries
+			.into_iter()
+			.filter_map(|entry| {
+				fuzzy_match(&self.query, &entry.label).map(|s| (s, entry))
+			})
+			.collect();
+
+		matched.sort_by(|(a, _), (b, _)| b.cmp(a));
+		matched.truncate(MAX_RESULTS);
+
+		matched.into_iter().map(|(_, entry)| entry).collect()
+	}
+}
@@ -0,0 +1,109 @@
+use similar::{ChangeTag, DiffOp, TextDiff};
+
+/// Unchanged lines kept around each change.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffTag {
+	Equal,
+	Delete,
+	Insert,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+	pub tag: DiffTag,
+	pub text: String,
+}
+
+/// Changed lines from `old` to `new` with some context, one group per run
+/// of nearby changes.
+pub fn diff_hunks(old: &str, new: &str) -> Vec<Vec<DiffLine>> {
+	let diff = TextDiff::from_lines(old, new);
+
+	diff.grouped_ops(CONTEXT_LINES)
+		.iter()
+		.map(|ops| {
+			ops.iter()
+				.flat_map(|op| diff.iter_changes(op))
+				.map(|change| DiffLine {
+					tag: match change.tag() {
+						ChangeTag::Equal => DiffTag::Equal,
+						ChangeTag::Delete => DiffTag::Delete,
+						ChangeTag::Insert => DiffTag::Insert,
+					},
+					text: change.value().trim_end_matches(['\r', '\n']).into(),
+				})
+				.collect()
+		})
+		.collect()
+}
+
+/// The number of lines changed from `old` to `new`, a replaced line counts
+/// once.
+pub fn count_changed_lines(old: &str, new: &str) -> usize {
+	TextDiff::from_lines(old, new)
+		.ops()
+		.iter()
+		.map(|op| match *op {
+			DiffOp::Equal { .. } => 0,
+			DiffOp::Delete { old_len, .. } => old_len,
+			DiffOp::Insert { new_len, .. } => new_len,
+			DiffOp::Replace {
+				old_len, new_len, ..
+			} => old_len.max(new_len),
+		})
+		.sum()
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::{count_changed_lines, diff_hunks, DiffLine, DiffTag};
+
+	#[rstest]
+	#[case::same("a\nb\n", "a\nb\n", 0)]
+	#[case::replace("a\nb\nc\n", "a\nB\nc\n", 1)]
+	#[case::insert("a\n", "a\nb\nc\n", 2)]
+	#[case::delete("a\nb\nc\n", "c\n", 2)]
+	#[case::replace_more("a\n", "b\nc\n", 2)]
+	fn test_count_changed_lines(
+		#[case] old: &str,
+		#[case] new: &str,
+		#[case] expected: usize,
+	) {
+		assert_eq!(count_changed_lines(old, new), expected);
+	}
+
+	#[test]
+	fn test_diff_hunks() {
+		let old = (0..20).map(|i| format!("{i}\n")).collect::<String>();
+		let new = (0..20)
+			.filter(|i| *i != 17)
+			.map(|i| {
+				if i == 2 {
+					"two\n".into()
+				} else {
+					format!("{i}\n")
+				}
+			})
+			.collect::<String>();
+		let hunks = diff_hunks(&old, &new);
+
+		assert_eq!(hunks.len(), 2);
+		let tags = |lines: &[DiffLine]| {
+			lines.iter().map(|line| line.tag).collect::<Vec<_>>()
+		};
+		assert_eq!(
+			tags(&hunks[1]),
+			[
+				vec![DiffTag::Equal; 3],
+				vec![DiffTag::Delete],
+				vec![DiffTag::Equal; 2]
+			]
+			.concat()
+		);
+		assert_eq!(hunks[0][2].text, "2");
+	}
+}
@@ -0,0 +1,390 @@
+use std::fmt;
+
+use anyhow::{bail, Result};
+
+use crate::i18n::t;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// How the hosts file was stored, so that saving writes it back the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileFormat {
+	pub encoding: Encoding,
+	pub bom: bool,
+	pub eol: Eol,
+	pub trailing_newline: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+	Utf8,
+	Utf16Le,
+	Utf16Be,
+	/// The ANSI code page of Windows, e.g. 936 (GBK) on Chinese systems.
+	#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+	Ansi(u32),
+}
+
+/// The file is not text that can be read or written in its encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingError {
+	Unknown,
+	Unencodable,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eol {
+	Lf,
+	CrLf,
+}
+
+impl Default for FileFormat {
+	fn default() -> Self {
+		Self {
+			encoding: Encoding::Utf8,
+			bom: false,
+			eol: Eol::Lf,
+			trailing_newline: true,
+		}
+	}
+}
+
+impl Eol {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Eol::Lf => "\n",
+			Eol::CrLf => "\r\n",
+		}
+	}
+
+	fn native() -> Self {
+		if cfg!(target_os = "windows") {
+			Eol::CrLf
+		} else {
+			Eol::Lf
+		}
+	}
+}
+
+impl fmt::Display for EncodingError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			EncodingError::Unknown => t("error.unknown_encoding"),
+			EncodingError::Unencodable => t("error.unencodable"),
+		})
+	}
+}
+
+impl std::error::Error for EncodingError {}
+
+impl FileFormat {
+	/// Detect the encoding, BOM and line endings of `bytes` and decode them,
+	/// the BOM is not part of the returned text.
+	pub fn decode(bytes: &[u8]) -> Result<(String, Self)> {
+		let (encoding, bom, body) =
+			if let Some(body) = bytes.strip_prefix(UTF8_BOM) {
+				(Encoding::Utf8, true, body)
+			} else if let Some(body) = bytes.strip_prefix(UTF16_LE_BOM) {
+				(Encoding::Utf16Le, true, body)
+			} else if let Some(body) = bytes.strip_prefix(UTF16_BE_BOM) {
+				(Encoding::Utf16Be, true, body)
+			} else {
+				(guess_encoding(bytes), false, bytes)
+			};
+
+		let text = match encoding {
+			Encoding::Utf8 => match std::str::from_utf8(body) {
+				Ok(text) => text.to_string(),
+				Err(_) => bail!(EncodingError::Unknown),
+			},
+			Encoding::Utf16Le => decode_utf16(body, u16::from_le_bytes)?,
+			Encoding::Utf16Be => decode_utf16(body, u16::from_be_bytes)?,
+			Encoding::Ansi(code_page) => ansi::decode(body, code_page)?,
+		};
+
+		let eol = match text.find('\n') {
+			Some(idx) if text[..idx].ends_with('\r') => Eol::CrLf,
+			Some(_) => Eol::Lf,
+			None => Eol::native(),
+		};
+		let trailing_newline = text.is_empty() || text.ends_with('\n');
+
+		let format = Self {
+			encoding,
+			bom,
+			eol,
+			trailing_newline,
+		};
+
+		Ok((text, format))
+	}
+
+	/// Encode `text` for saving, with the line endings of the original file.
+	pub fn encode(&self, text: &str) -> Result<Vec<u8>> {
+		let text = text.replace("\r\n", "\n");
+		let text = match self.eol {
+			Eol::Lf => text,
+			Eol::CrLf => text.replace('\n', "\r\n"),
+		};
+
+		let mut bytes = vec![];
+		match self.encoding {
+			Encoding::Utf8 => {
+				if self.bom {
+					bytes.extend_from_slice(UTF8_BOM);
+				}
+				bytes.extend_from_slice(text.as_bytes());
+			}
+			Encoding::Utf16Le => {
+				if self.bom {
+					bytes.extend_from_slice(UTF16_LE_BOM);
+				}
+				bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+			}
+			Encoding::Utf16Be => {
+				if self.bom {
+					bytes.extend_from_slice(UTF16_BE_BOM);
+				}
+				bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+			}
+			Encoding::Ansi(code_page) => {
+				bytes = ansi::encode(&text, code_page)?;
+			}
+		}
+
+		Ok(bytes)
+	}
+}
+
+/// Guess the encoding of a file without BOM. UTF-16 is recognized by the zero
+/// bytes of ASCII characters, which hosts files are mostly made of.
+fn guess_encoding(bytes: &[u8]) -> Encoding {
+	// NUL is valid UTF-8 but never appears in a text file
+	if !bytes.contains(&0) && std::str::from_utf8(bytes).is_ok() {
+		return Encoding::Utf8;
+	}
+
+	if bytes.len() >= 2 && bytes.len().is_multiple_of(2) {
+		let pairs = bytes.len() / 2;
+		let zero_at = |offset: usize| {
+			bytes
+				.iter()
+				.skip(offset)
+				.step_by(2)
+				.filter(|b| **b == 0)
+				.count()
+		};
+		if zero_at(1) * 2 > pairs {
+			return Encoding::Utf16Le;
+		}
+		if zero_at(0) * 2 > pairs {
+			return Encoding::Utf16Be;
+		}
+	}
+
+	ansi::fallback_encoding()
+}
+
+fn decode_utf16(bytes: &[u8], to_u16: fn([u8; 2]) -> u16) -> Result<String> {
+	if !bytes.len().is_multiple_of(2) {
+		bail!(EncodingError::Unknown);
+	}
+	let units = bytes.chunks_exact(2).map(|pair| to_u16([pair[0], pair[1]]));
+
+	match char::decode_utf16(units).collect::<Result<String, _>>() {
+		Ok(text) => Ok(text),
+		Err(_) => bail!(EncodingError::Unknown),
+	}
+}
+
+#[cfg(target_os = "windows")]
+mod ansi {
+	use anyhow::{bail, Result};
+
+	use super::{Encoding, EncodingError};
+
+	const MB_ERR_INVALID_CHARS: u32 = 0x8;
+
+	#[link(name = "kernel32")]
+	extern "system" {
+		fn GetACP() -> u32;
+		fn MultiByteToWideChar(
+			code_page: u32,
+			flags: u32,
+			src: *const u8,
+			src_len: i32,
+			dst: *mut u16,
+			dst_len: i32,
+		) -> i32;
+		fn WideCharToMultiByte(
+			code_page: u32,
+			flags: u32,
+			src: *const u16,
+			src_len: i32,
+			dst: *mut u8,
+			dst_len: i32,
+			default_char: *const u8,
+			used_default_char: *mut i32,
+		) -> i32;
+	}
+
+	pub fn fallback_encoding() -> Encoding {
+		Encoding::Ansi(unsafe { GetACP() })
+	}
+
+	pub fn decode(bytes: &[u8], code_page: u32) -> Result<String> {
+		if bytes.is_empty() {
+			return Ok(String::new());
+		}
+		let convert = |dst: *mut u16, dst_len: i32| unsafe {
+			MultiByteToWideChar(
+				code_page,
+				MB_ERR_INVALID_CHARS,
+				bytes.as_ptr(),
+				bytes.len() as i32,
+				dst,
+				dst_len,
+			)
+		};
+
+		let len = convert(std::ptr::null_mut(), 0);
+		if len <= 0 {
+			bail!(EncodingError::Unknown);
+		}
+		let mut wide = vec![0u16; len as usize];
+		if convert(wide.as_mut_ptr(), len) != len {
+			bail!(EncodingError::Unknown);
+		}
+
+		Ok(String::from_utf16_lossy(&wide))
+	}
+
+	pub fn encode(text: &str, code_page: u32) -> Result<Vec<u8>> {
+		if text.is_empty() {
+			return Ok(vec![]);
+		}
+		let wide: Vec<u16> = text.encode_utf16().collect();
+		let mut used_default = 0;
+		let mut convert = |dst: *mut u8, dst_len: i32| unsafe {
+			WideCharToMultiByte(
+				code_page,
+				0,
+				wide.as_ptr(),
+				wide.len() as i32,
+				dst,
+				dst_len,
+				std::ptr::null(),
+				&mut used_default,
+			)
+		};
+
+		let len = convert(std::ptr::null_mut(), 0);
+		if len <= 0 {
+			bail!(EncodingError::Unencodable);
+		}
+		let mut bytes = vec![0u8; len as usize];
+		if convert(bytes.as_mut_ptr(), len) != len || used_default != 0 {
+			bail!(EncodingError::Unencodable);
+		}
+
+		Ok(bytes)
+	}
+}
+
+#[cfg(not(target_os = "windows"))]
+mod ansi {
+	use anyhow::{bail, Result};
+
+	use super::{Encoding, EncodingError};
+
+	/// Legacy code pages are only decoded on Windows, elsewhere the file is
+	/// left to fail as UTF-8.
+	pub fn fallback_encoding() -> Encoding {
+		Encoding::Utf8
+	}
+
+	pub fn decode(_bytes: &[u8], _code_page: u32) -> Result<String> {
+		bail!(EncodingError::Unknown)
+	}
+
+	pub fn encode(_text: &str, _code_page: u32) -> Result<Vec<u8>> {
+		bail!(EncodingError::Unencodable)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::{Encoding, Eol, FileFormat};
+
+	#[rstest]
+	#[case::utf8(b"127.0.0.1 a.test\n".to_vec(), Encoding::Utf8, false)]
+	#[case::utf8_bom(
+		b"\xEF\xBB\xBF127.0.0.1 a.test\n".to_vec(),
+		Encoding::Utf8,
+		true
+	)]
+	#[case::utf16_le_bom(
+		utf16(&[0xFF, 0xFE], "127.0.0.1 a.test\n", u16::to_le_bytes),
+		Encoding::Utf16Le,
+		true
+	)]
+	#[case::utf16_be_bom(
+		utf16(&[0xFE, 0xFF], "127.0.0.1 a.test\n", u16::to_be_bytes),
+		Encoding::Utf16Be,
+		true
+	)]
+	#[case::utf16_le(
+		utf16(&[], "127.0.0.1 a.test\n", u16::to_le_bytes),
+		Encoding::Utf16Le,
+		false
+	)]
+	#[case::utf16_be(
+		utf16(&[], "127.0.0.1 a.test # 注释\n", u16::to_be_bytes),
+		Encoding::Utf16Be,
+		false
+	)]
+	fn test_encoding_round_trip(
+		#[case] bytes: Vec<u8>,
+		#[case] encoding: Encoding,
+		#[case] bom: bool,
+	) {
+		let (text, format) = FileFormat::decode(&bytes).unwrap();
+
+		assert!(text.starts_with("127.0.0.1 a.test"));
+		assert_eq!(format.encoding, encoding);
+		assert_eq!(format.bom, bom);
+		assert_eq!(format.encode(&text).unwrap(), bytes);
+	}
+
+	#[test]
+	fn test_line_endings() {
+		let (text, format) = FileFormat::decode(b"a\r\nb").unwrap();
+		assert_eq!(format.eol, Eol::CrLf);
+		assert!(!format.trailing_newline);
+		assert_eq!(
+			format.encode(&format!("{}\nc", text)).unwrap(),
+			b"a\r\nb\r\nc"
+		);
+
+		let (_, format) = FileFormat::decode(b"a\nb\r\n").unwrap();
+		assert_eq!(format.eol, Eol::Lf);
+		assert!(format.trailing_newline);
+	}
+
+	#[cfg(not(target_os = "windows"))]
+	#[test]
+	fn test_unknown_encoding() {
+		// "中文" in GBK
+		assert!(FileFormat::decode(b"\xD6\xD0\xCE\xC4").is_err());
+	}
+
+	fn utf16(bom: &[u8], s: &str, to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+		let mut bytes = bom.to_vec();
+		bytes.extend(s.encode_utf16().flat_map(to_bytes));
+		bytes
+	}
+}
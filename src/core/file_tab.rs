@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use super::{
+	hosts_error::HostsError, selection::HostSelection, toast::SavedChange,
+	HostsInfo,
+};
+
+/// An open hosts file. The one of the active tab lives in `Hed` and is
+/// swapped in when its tab is activated.
+#[derive(Default)]
+pub struct FileTab {
+	/// Empty for the system hosts file until it is resolved.
+	pub path: PathBuf,
+	pub is_system: bool,
+	pub loaded: bool,
+	pub read_only: bool,
+	pub error: Option<HostsError>,
+	pub hosts_info: HostsInfo,
+	pub hosts_info_draft: HostsInfo,
+	pub selection: HostSelection,
+	/// Whether the file is being read or written.
+	pub loading: bool,
+	pub saving: Option<SavedChange>,
+	pub notify_reload: bool,
+}
+
+impl FileTab {
+	pub fn new(path: PathBuf) -> Self {
+		Self {
+			path,
+			..Default::default()
+		}
+	}
+
+	pub fn is_dirty(&self) -> bool {
+		self.loaded && self.hosts_info_draft.content != self.hosts_info.content
+	}
+}
@@ -0,0 +1,256 @@
+use anyhow::Result;
+use regex::{NoExpand, Regex};
+
+use super::HostsInfo;
+use crate::util::is_ip;
+
+#[derive(Default)]
+pub struct FindReplace {
+	pub find: String,
+	pub replace: String,
+	pub use_regex: bool,
+	pub target: ReplaceTarget,
+	pub find_error: String,
+	/// The compiled `find`, kept while the pattern stays the same.
+	matcher: Option<(MatcherKey, Result<Regex, regex::Error>)>,
+}
+
+/// The find text, whether it is a regex and whether it must match the whole
+/// field.
+type MatcherKey = (String, bool, bool);
+
+#[derive(PartialEq, Eq, Default, Clone, Copy, Debug)]
+pub enum ReplaceTarget {
+	#[default]
+	Ip,
+	Host,
+	Text,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplaceLocation {
+	Ip(usize),
+	Host(usize, usize),
+	Line(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Replacement {
+	pub location: ReplaceLocation,
+	pub old: String,
+	pub new: String,
+	pub valid: bool,
+}
+
+impl FindReplace {
+	pub fn reset(&mut self) {
+		self.find.clear();
+		self.replace.clear();
+		self.use_regex = false;
+		self.find_error.clear();
+	}
+
+	fn matcher(&mut self) -> Result<Regex> {
+		// a plain ip only replaces whole ips, `10.0.3.1` is not part of
+		// `10.0.3.17`
+		let whole = !self.use_regex && self.target == ReplaceTarget::Ip;
+		let key = (self.find.clone(), self.use_regex, whole);
+		if let Some((cached, re)) = &self.matcher {
+			if *cached == key {
+				return Ok(re.clone()?);
+			}
+		}
+
+		let re = if self.use_regex {
+			Regex::new(&self.find)
+		} else if whole {
+			Regex::new(&format!("^{}$", regex::escape(&self.find)))
+		} else {
+			Regex::new(&regex::escape(&self.find))
+		};
+		self.matcher = Some((key, re.clone()));
+
+		Ok(re?)
+	}
+
+	fn replace_str(&self, re: &Regex, s: &str) -> Option<String> {
+		if !re.is_match(s) {
+			return None;
+		}
+
+		let new = if self.use_regex {
+			re.replace_all(s, self.replace.as_str())
+		} else {
+			re.replace_all(s, NoExpand(&self.replace))
+		};
+
+		if new == s {
+			None
+		} else {
+			Some(new.into_owned())
+		}
+	}
+
+	pub fn preview(
+		&mut self,
+		hosts_info: &HostsInfo,
+	) -> Result<Vec<Replacement>> {
+		if self.find.is_empty() {
+			return Ok(vec![]);
+		}
+
+		let re = self.matcher()?;
+		let mut replacements = vec![];
+
+		match self.target {
+			ReplaceTarget::Ip => {
+				for item in &hosts_info.list {
+					if let Some(new) = self.replace_str(&re, &item.ip) {
+						replacements.push(Replacement {
+							location: ReplaceLocation::Ip(item.id),
+							old: item.ip.clone(),
+							valid: is_ip(&new),
+							new,
+						});
+					}
+				}
+			}
+			ReplaceTarget::Host => {
+				for item in &hosts_info.list {
+					for host in &item.hosts {
+						if let Some(new) = self.replace_str(&re, &host.name) {
+							replacements.push(Replacement {
+								location: ReplaceLocation::Host(
+									item.id, host.id,
+								),
+								old: host.name.clone(),
+								valid: is_host_name(&new),
+								new,
+							});
+						}
+					}
+				}
+			}
+			ReplaceTarget::Text => {
+				for (i, line) in hosts_info.content.lines().enumerate() {
+					if let Some(new) = self.replace_str(&re, line) {
+						replacements.push(Replacement {
+							location: ReplaceLocation::Line(i),
+							old: line.to_string(),
+							new,
+							valid: true,
+						});
+					}
+				}
+			}
+		}
+
+		Ok(replacements)
+	}
+}
+
+fn is_host_name(s: &str) -> bool {
+	!s.is_empty() && !s.contains(char::is_whitespace) && !s.contains('#')
+}
+
+#[cfg(test)]
+mod tests {
+	use insta::assert_debug_snapshot;
+
+	use super::{FindReplace, ReplaceTarget};
+	use crate::core::HostsInfo;
+
+	fn hosts_info() -> HostsInfo {
+		let mut hosts_info = HostsInfo::default();
+		hosts_info.content =
+			"10.0.3.17 a.com b.com\n127.0.0.1 ads.a.com\n10.0.4.2 c.com\n"
+				.to_string();
+		hosts_info.update_list();
+		for (i, item) in hosts_info.list.iter_mut().enumerate() {
+			item.id = i;
+			for (j, host) in item.hosts.iter_mut().enumerate() {
+				host.id = j;
+			}
+		}
+		hosts_info
+	}
+
+	#[test]
+	fn test_replace_ip_merge() {
+		let mut hosts_info = hosts_info();
+		let mut form = FindReplace {
+			find: "10.0.3.17".to_string(),
+			replace: "10.0.4.2".to_string(),
+			target: ReplaceTarget::Ip,
+			..Default::default()
+		};
+		let replacements = form.preview(&hosts_info).unwrap();
+		hosts_info.apply_replacements(&replacements);
+
+		assert_debug_snapshot!("replace_ip_merge", hosts_info.content);
+	}
+
+	#[test]
+	fn test_replace_host_regex() {
+		let mut hosts_info = hosts_info();
+		let mut form = FindReplace {
+			find: r"^(\w+)\.a\.com$".to_string(),
+			replace: "$1.b.com".to_string(),
+			use_regex: true,
+			target: ReplaceTarget::Host,
+			..Default::default()
+		};
+		let replacements = form.preview(&hosts_info).unwrap();
+		hosts_info.apply_replacements(&replacements);
+
+		assert_debug_snapshot!("replace_host_regex", hosts_info.content);
+	}
+
+	#[test]
+	fn test_replace_text() {
+		let mut hosts_info = hosts_info();
+		let mut form = FindReplace {
+			find: "127.0.0.1".to_string(),
+			replace: "0.0.0.0".to_string(),
+			target: ReplaceTarget::Text,
+			..Default::default()
+		};
+		let replacements = form.preview(&hosts_info).unwrap();
+		hosts_info.apply_replacements(&replacements);
+
+		assert_debug_snapshot!("replace_text", hosts_info.content);
+	}
+
+	#[test]
+	fn test_replace_whole_ip() {
+		let mut hosts_info = HostsInfo::default();
+		hosts_info.content = "10.0.3.170 a.com\n10.0.3.17 b.com\n".to_string();
+		hosts_info.update_list();
+		let mut form = FindReplace {
+			find: "10.0.3.17".to_string(),
+			replace: "10.0.4.2".to_string(),
+			target: ReplaceTarget::Ip,
+			..Default::default()
+		};
+		let replacements = form.preview(&hosts_info).unwrap();
+
+		assert_eq!(replacements.len(), 1);
+		assert_eq!(replacements[0].old, "10.0.3.17");
+		assert_eq!(replacements[0].new, "10.0.4.2");
+	}
+
+	#[test]
+	fn test_replace_invalid_ip() {
+		let hosts_info = hosts_info();
+		let mut form = FindReplace {
+			find: "10.0.3.17".to_string(),
+			replace: "foo".to_string(),
+			target: ReplaceTarget::Ip,
+			..Default::default()
+		};
+		let replacements = form.preview(&hosts_info).unwrap();
+
+		assert_eq!(replacements.len(), 1);
+		assert!(!replacements[0].valid);
+	}
+}
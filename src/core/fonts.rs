@@ -0,0 +1,38 @@
+use std::{fs, path::PathBuf};
+
+use crate::util::get_fallback_fonts;
+
+/// CJK fonts are large, only the first few found are loaded.
+const MAX_FALLBACK_FONTS: usize = 3;
+
+/// Font files read off the UI thread, named by their path.
+pub struct LoadedFonts {
+	pub custom_path: Option<PathBuf>,
+	/// The custom font, or why it could not be read.
+	pub custom: Option<Result<Vec<u8>, String>>,
+	pub fallbacks: Vec<(String, Vec<u8>)>,
+}
+
+impl LoadedFonts {
+	/// Read the custom font and look for system fonts for the scripts the
+	/// bundled ones don't cover, which walks the font directories.
+	pub fn load(custom_path: Option<PathBuf>) -> Self {
+		let custom = custom_path
+			.as_ref()
+			.map(|path| fs::read(path).map_err(|err| err.to_string()));
+		let fallbacks = get_fallback_fonts()
+			.into_iter()
+			.filter_map(|path| {
+				let data = fs::read(&path).ok()?;
+				Some((path.to_string_lossy().into_owned(), data))
+			})
+			.take(MAX_FALLBACK_FONTS)
+			.collect();
+
+		Self {
+			custom_path,
+			custom,
+			fallbacks,
+		}
+	}
+}
@@ -1,258 +1,1313 @@
-use std::{path::PathBuf, thread};
+use std::{
+	collections::{HashMap, VecDeque},
+	iter, mem,
+	path::{Path, PathBuf},
+	thread,
+	time::{Duration, Instant},
+};
 
-use anyhow::Result;
+use egui::Context;
 
 use super::{
+	audit::{AuditRules, Finding},
+	autosave::Autosave,
 	channel::{Channel, Event},
+	command::{Command, CommandEntry, CommandPalette},
+	diff::{count_changed_lines, diff_hunks, DiffLine},
+	file_tab::FileTab,
+	find_replace::{FindReplace, ReplaceLocation, ReplaceTarget, Replacement},
+	fonts::LoadedFonts,
+	host_timer::{HostTimer, TimerForm, TimerTarget},
+	hosts_error::{FileOp, HostsError, Recovery},
+	hosts_options::HostsOptions,
+	item::Host,
 	item_form::ItemForm,
+	lint::{apply_fix, lint, Lint, Platform},
+	normalize::NormalizeSteps,
+	path_form::PathForm,
+	protected::ProtectedHosts,
+	selection::{HostKey, HostSelection, SelectMode},
+	settings::{Settings, Theme},
+	toast::{SavedChange, ToastAction, ToastLevel, Toasts},
 	HostsInfo,
 };
-use crate::util::{get_sys_hosts_path, StringExt};
+use crate::{
+	i18n::{set_lang, t, tf},
+	util::{get_sys_hosts_path, is_ip, now_secs, StringExt},
+};
+
+/// How long typing in the text view pauses before the list follows.
+const UPDATE_LIST_DELAY: Duration = Duration::from_millis(300);
+
+/// The least time between two autosaves of the draft.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Default)]
 pub struct Hed {
 	channel: Channel,
 	#[cfg(feature = "_dev")]
 	pub dev_window_open: bool,
-	pub sys_hosts_path: PathBuf,
+	/// Open files, the slot of the active one is left empty while its state
+	/// is in the fields below.
+	tabs: Vec<FileTab>,
+	pub active_tab: usize,
+	pub hosts_path: PathBuf,
+	pub is_system: bool,
+	/// Whether the file of the active tab is being read or written.
 	pub sys_hosts_loading: bool,
-	pub os_err: String,
+	/// The last failure to read or write a hosts file, shown until it is
+	/// recovered from or dismissed.
+	pub error: Option<HostsError>,
+	/// Whether the hosts file was read, the draft is kept through errors
+	/// once it was.
+	pub loaded: bool,
+	pub read_only: bool,
 	pub hosts_info: HostsInfo,
 	pub hosts_info_draft: HostsInfo,
-	pub view_kind: ViewKind,
+	pub settings: Settings,
+	pub settings_draft: Settings,
+	pub recording_shortcut: Option<Command>,
+	style_changed: bool,
+	/// Fonts read by a worker thread, until they are set.
+	loaded_fonts: Option<LoadedFonts>,
 	pub view_all: bool,
 	pub search_ip_hosts: String,
+	/// Loaded when the hosts file is first audited.
+	audit_rules: Option<AuditRules>,
+	/// Suspicious hosts of the draft, as of `audited_revision`.
+	pub findings: Vec<Finding>,
+	audited_revision: Option<usize>,
+	/// The ip and host of the findings last told about, by file, so that
+	/// reading the file again after a save doesn't tell again.
+	notified_findings: HashMap<PathBuf, Vec<(String, String)>>,
+	/// Quirks of the draft on the lint platform, as of `linted`.
+	pub lints: Vec<Lint>,
+	linted: Option<(usize, Platform)>,
+	protected_hosts: ProtectedHosts,
+	/// Events touching protected hosts, with their names, until each is
+	/// confirmed or cancelled in turn.
+	pending_protected: VecDeque<(Event, Vec<String>)>,
 	pub item_form: ItemForm,
+	pub find_replace: FindReplace,
+	pub selection: HostSelection,
+	pub command_palette: CommandPalette,
+	pub timer_form: TimerForm,
+	pub path_form: PathForm,
+	pub toasts: Toasts,
+	/// Hunks shown in the diff window.
+	pub diff: Vec<Vec<DiffLine>>,
+	pub normalize_steps: NormalizeSteps,
+	/// The draft content with `normalize_steps` applied, while previewed.
+	normalized: Option<String>,
+	/// The save in progress, for the toast once it is done.
+	saving: Option<SavedChange>,
+	notify_reload: bool,
+	/// What waits for the user to save or discard the draft.
+	pub pending_discard: Option<DiscardAction>,
+	/// A close that waits for files being read or written.
+	close_pending: bool,
+	close_requested: bool,
+	/// A draft left by the last run, until it is restored or discarded.
+	pub restore: Option<Autosave>,
+	restore_checked: bool,
+	has_autosave: bool,
+	autosaved_revision: Option<usize>,
+	autosaved_at: Option<Instant>,
+	pub scroll_to_item: Option<usize>,
 	pub opened_window: Option<OpenedWindow>,
+	content_edited_at: Option<Instant>,
 	selected_item_id: Option<usize>,
 	selected_host_id: Option<usize>,
 }
 
-#[derive(PartialEq, Eq, Default)]
+#[derive(PartialEq, Eq, Default, Debug, Clone, Copy)]
 pub enum ViewKind {
 	#[default]
 	Options,
 	Text,
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum OpenedWindow {
 	NewItem,
 	AddHosts,
 	EditHost,
+	FindReplace,
+	MoveHosts,
+	CopyHosts,
+	Settings,
+	CommandPalette,
+	Timer,
+	OpenFile,
+	SaveAs,
+	Diff,
+	ConfirmDiscard,
+	RestoreDraft,
+	ConfirmProtected,
+	Audit,
+	ImportAuditRules,
+	Normalize,
+}
+
+/// Something that would drop the unsaved draft.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum DiscardAction {
+	Close,
+	CloseTab,
+	Reload,
+	/// Applying settings that point the system tab to another file.
+	ChangeHostsPath,
 }
 
 impl Hed {
-	pub fn init(&mut self) {
-		self.parse_sys_hosts();
+	/// Open the system hosts file and one more tab for each of `paths`,
+	/// the first of them is shown.
+	pub fn init(
+		&mut self,
+		ctx: &Context,
+		settings: Settings,
+		paths: &[PathBuf],
+	) {
+		self.channel.set_ctx(ctx.clone());
+		set_lang(settings.lang());
+		self.protected_hosts =
+			ProtectedHosts::with_system_hostname(&settings.protected_hosts);
+		self.settings = settings;
+		self.style_changed = true;
+		self.load_fonts();
+		self.tabs.push(FileTab::default());
+		self.is_system = true;
+		self.tabs.extend(paths.iter().cloned().map(FileTab::new));
+		if paths.is_empty() {
+			self.parse_sys_hosts();
+		} else {
+			self.switch_tab(1);
+		}
 	}
 
-	pub fn handle_event(&mut self) {
-		use Event::*;
+	pub fn tab_count(&self) -> usize {
+		self.tabs.len().max(1)
+	}
 
-		if let Some(event) = self.channel.recv() {
-			match event {
-				ParseHostsOk(hosts_info) => {
-					self.handle_parse_hosts_ok(hosts_info);
-				}
-				OsErr(err) => {
-					self.handle_os_err(err);
-				}
-				EditItemIp(item_id, ip) => {
-					self.edit_item_ip(item_id, ip);
-				}
-				ToggleHostEnable(item_id, host_id) => {
-					self.toggle_host_enable(item_id, host_id);
-				}
-				DeleteItem(item_id) => {
-					self.delete_item(item_id);
-				}
-				DeleteHost(item_id, host_id) => {
-					self.delete_host(item_id, host_id);
-				}
-				OpenAddHostsWindow(item_id) => {
-					self.open_add_hosts_window(item_id);
-				}
-				OpenEditHostWindow(item_id, host_id) => {
-					self.open_edit_host_window(item_id, host_id);
-				}
-				SaveHostsOk => {
-					self.parse_sys_hosts();
-				}
-				ToggleAllHostEnable(item_id, enabled) => {
-					self.toggle_all_host_enable(item_id, enabled);
-				}
+	/// The path and whether there are unsaved changes of tab `idx`.
+	pub fn tab_info(&self, idx: usize) -> (&Path, bool, bool) {
+		if idx == self.active_tab {
+			return (&self.hosts_path, self.is_system, self.is_dirty());
+		}
+		let tab = &self.tabs[idx];
+		(&tab.path, tab.is_system, tab.is_dirty())
+	}
+
+	fn is_dirty(&self) -> bool {
+		self.loaded && self.is_hosts_changed()
+	}
+
+	/// Put the state of the active tab back into its slot and take the one
+	/// of tab `idx` out.
+	fn activate_tab(&mut self, idx: usize) {
+		for idx in [self.active_tab, idx] {
+			let tab = &mut self.tabs[idx];
+			mem::swap(&mut tab.path, &mut self.hosts_path);
+			mem::swap(&mut tab.is_system, &mut self.is_system);
+			mem::swap(&mut tab.loaded, &mut self.loaded);
+			mem::swap(&mut tab.read_only, &mut self.read_only);
+			mem::swap(&mut tab.error, &mut self.error);
+			mem::swap(&mut tab.hosts_info, &mut self.hosts_info);
+			mem::swap(&mut tab.hosts_info_draft, &mut self.hosts_info_draft);
+			mem::swap(&mut tab.selection, &mut self.selection);
+			mem::swap(&mut tab.loading, &mut self.sys_hosts_loading);
+			mem::swap(&mut tab.saving, &mut self.saving);
+			mem::swap(&mut tab.notify_reload, &mut self.notify_reload);
+		}
+		self.active_tab = idx;
+	}
+
+	/// Show tab `idx`, its file is read the first time it is shown.
+	pub fn switch_tab(&mut self, idx: usize) {
+		if idx == self.active_tab || idx >= self.tabs.len() {
+			return;
+		}
+		self.flush_content_edit();
+		self.activate_tab(idx);
+		if !self.loaded && !self.sys_hosts_loading && self.error.is_none() {
+			self.parse_sys_hosts();
+		}
+	}
+
+	/// Run `f` with the tab of `path` active, the tab shown before is shown
+	/// again after. Nothing is run if the tab was closed.
+	fn in_tab(&mut self, path: &Path, f: impl FnOnce(&mut Self)) {
+		let find = |hed: &Self, path: &Path| {
+			(0..hed.tab_count()).find(|idx| hed.tab_info(*idx).0 == path)
+		};
+		let Some(idx) = find(self, path) else {
+			return;
+		};
+		if idx == self.active_tab {
+			f(self);
+			return;
+		}
+		let shown = self.hosts_path.clone();
+		self.activate_tab(idx);
+		f(self);
+		if let Some(idx) = find(self, &shown) {
+			self.activate_tab(idx);
+		}
+	}
+
+	fn any_loading(&self) -> bool {
+		self.sys_hosts_loading || self.tabs.iter().any(|tab| tab.loading)
+	}
+
+	/// Open `path` in a tab, or show its tab if it is open already.
+	pub fn open_path(&mut self, path: PathBuf) {
+		let open_idx = (0..self.tab_count())
+			.find(|idx| self.tab_info(*idx).0 == path.as_path());
+		if let Some(idx) = open_idx {
+			self.switch_tab(idx);
+			if !self.sys_hosts_loading && !self.is_hosts_changed() {
+				self.parse_sys_hosts();
 			}
+			return;
 		}
+		if self.tabs.is_empty() {
+			self.tabs.push(FileTab::default());
+		}
+		self.tabs.push(FileTab::new(path));
+		self.switch_tab(self.tabs.len() - 1);
 	}
 
-	pub fn send_event(&self, event: Event) {
-		self.channel.send(event);
+	/// Close tab `idx`, asks first when it has unsaved changes. The tab of
+	/// the system hosts file stays open.
+	pub fn close_tab(&mut self, idx: usize) {
+		if idx == 0 || idx >= self.tabs.len() {
+			return;
+		}
+		self.switch_tab(idx);
+		if self.is_dirty() {
+			self.confirm_discard(DiscardAction::CloseTab);
+			return;
+		}
+		self.remove_active_tab();
 	}
 
-	pub fn parse_sys_hosts(&mut self) {
-		if self.sys_hosts_path.to_string_lossy().is_empty() {
-			let Ok(sys_hosts_path) = get_sys_hosts_path() else {
-				self.os_err =
-					"Failed to get the path of system hosts file".to_string();
-				return;
-			};
-			self.sys_hosts_path = sys_hosts_path.clone();
+	fn remove_active_tab(&mut self) {
+		let idx = self.active_tab;
+		if idx == 0 {
+			return;
 		}
-		self.sys_hosts_loading = true;
-		let tx = self.channel.tx.clone();
-		let hosts_path = self.sys_hosts_path.clone();
-		thread::spawn(move || -> Result<()> {
-			match HostsInfo::parse_from_file(hosts_path) {
-				Ok(hosts_info) => {
-					tx.send(Event::ParseHostsOk(hosts_info))?;
+		self.content_edited_at = None;
+		self.activate_tab(idx - 1);
+		self.tabs.remove(idx);
+		if !self.loaded && self.error.is_none() {
+			self.parse_sys_hosts();
+		}
+	}
+
+	/// Handle every queued event in the order they were sent, including the
+	/// ones sent while handling.
+	pub fn handle_events(&mut self) {
+		while let Some(event) = self.channel.recv() {
+			self.handle_event(event);
+			if self.close_pending && !self.any_loading() {
+				self.close_pending = false;
+				self.close_requested = self.request_close();
+			}
+		}
+	}
+
+	fn handle_event(&mut self, event: Event) {
+		self.flush_content_edit();
+		let names = self.protected_names(&event);
+		if names.is_empty() {
+			self.apply_event(event);
+			// keys of hosts that were removed or moved would linger
+			let keys = self.hosts_info_draft.host_keys();
+			self.selection.retain_existing(&keys);
+		} else {
+			self.pending_protected.push_back((event, names));
+			self.set_opened_window(OpenedWindow::ConfirmProtected);
+		}
+	}
+
+	fn apply_event(&mut self, event: Event) {
+		use Event::*;
+
+		match event {
+			ParseHostsOk(path, hosts_info) => {
+				self.in_tab(&path, |hed| hed.handle_parse_hosts_ok(hosts_info));
+			}
+			HostsErr(path, error) => {
+				self.in_tab(&path, |hed| hed.handle_hosts_err(error));
+			}
+			EditItemIp(item_id, ip) => {
+				self.edit_item_ip(item_id, ip);
+			}
+			ToggleHostEnable(item_id, host_id) => {
+				self.toggle_host_enable(item_id, host_id);
+			}
+			DeleteItem(item_id) => {
+				self.delete_item(item_id);
+			}
+			DeleteHost(item_id, host_id) => {
+				self.delete_host(item_id, host_id);
+			}
+			RenameHost(item_id, host_id, name) => {
+				self.rename_host(item_id, host_id, name);
+			}
+			FixLint(lint) => {
+				self.fix_lint(lint);
+			}
+			ApplyReplacements(replacements) => {
+				self.hosts_info_draft.apply_replacements(&replacements);
+			}
+			RestoreHosts(ip, hosts) => {
+				self.hosts_info_draft.restore_hosts(&ip, hosts);
+				self.update_content();
+			}
+			OpenAddHostsWindow(item_id) => {
+				self.open_add_hosts_window(item_id);
+			}
+			OpenEditHostWindow(item_id, host_id) => {
+				self.open_edit_host_window(item_id, host_id);
+			}
+			SaveHostsOk(path) => {
+				self.in_tab(&path, Self::handle_save_hosts_ok);
+			}
+			SaveAsOk(path, target) => {
+				self.in_tab(&path, |hed| hed.sys_hosts_loading = false);
+				let message = tf("toast.saved_as", &[&target.display()]);
+				self.toasts.push(ToastLevel::Success, message);
+				self.open_path(target);
+			}
+			ToggleAllHostEnable(item_id, enabled) => {
+				self.toggle_all_host_enable(item_id, enabled);
+			}
+			SelectHost(key, mode) => {
+				self.select_host(key, mode);
+			}
+			ToggleHostsEnable(keys, enabled) => {
+				self.toggle_hosts_enable(keys, enabled);
+			}
+			DeleteHosts(keys) => {
+				self.delete_hosts(keys);
+			}
+			MoveHosts(keys, ip) => {
+				self.transfer_hosts(keys, ip, true);
+			}
+			CopyHosts(keys, ip) => {
+				self.transfer_hosts(keys, ip, false);
+			}
+			MoveItem(item_id, to) => {
+				self.move_item(item_id, to);
+			}
+			MoveHost(key, to_item_id, to) => {
+				self.move_host(key, to_item_id, to);
+			}
+			OpenTimerWindow(target) => {
+				self.open_timer_window(target);
+			}
+			CancelTimer(key) => {
+				self.cancel_timer(key);
+			}
+			FontsLoaded(fonts) => {
+				self.handle_fonts_loaded(fonts);
+			}
+			ExpiredTimersSaved(path, hosts_info) => {
+				self.in_tab(&path, |hed| {
+					hed.sys_hosts_loading = false;
+					hed.hosts_info = hosts_info;
+				});
+				let message = t("toast.timers_reverted").to_string();
+				self.toasts.push(ToastLevel::Info, message);
+			}
+		}
+	}
+
+	pub fn is_protected(&self, name: &str) -> bool {
+		self.protected_hosts.contains(name)
+	}
+
+	/// Protected hosts that `event` would disable, delete, rename or point
+	/// to another ip, also once a timer set by it expires.
+	fn protected_names(&self, event: &Event) -> Vec<String> {
+		let item_keys = |item_id: usize| -> Vec<HostKey> {
+			self.hosts_info_draft
+				.list
+				.iter()
+				.filter(|item| item.id == item_id)
+				.flat_map(|item| {
+					item.hosts.iter().map(|host| (item.id, host.id))
+				})
+				.collect()
+		};
+		let item_ip = |item_id: usize| {
+			self.hosts_info_draft
+				.list
+				.iter()
+				.find(|item| item.id == item_id)
+				.map(|item| item.ip.as_str())
+		};
+		let (keys, disabling) = match event {
+			Event::ToggleHostEnable(item_id, host_id) => {
+				(vec![(*item_id, *host_id)], true)
+			}
+			Event::DeleteHost(item_id, host_id) => {
+				(vec![(*item_id, *host_id)], false)
+			}
+			Event::RenameHost(item_id, host_id, name) => {
+				let key = (*item_id, *host_id);
+				if self.find_host(key).is_some_and(|host| host.name == *name) {
+					return vec![];
 				}
-				Err(err) => {
-					tx.send(Event::OsErr(err.to_string()))?;
+				(vec![key], false)
+			}
+			Event::DeleteItem(item_id) => (item_keys(*item_id), false),
+			Event::ToggleAllHostEnable(item_id, false) => {
+				(item_keys(*item_id), true)
+			}
+			Event::ToggleHostsEnable(keys, false) => (keys.clone(), true),
+			Event::DeleteHosts(keys) | Event::MoveHosts(keys, _) => {
+				(keys.clone(), false)
+			}
+			Event::EditItemIp(item_id, ip) => {
+				// an ip still being typed is not applied yet
+				if item_ip(*item_id) == Some(ip.as_str()) || !is_ip(ip) {
+					return vec![];
 				}
+				(item_keys(*item_id), false)
 			}
-			Ok(())
-		});
+			Event::MoveHost(key, to_item_id, _) => {
+				if item_ip(key.0) == item_ip(*to_item_id) {
+					return vec![];
+				}
+				(vec![*key], false)
+			}
+			// a timer disables its hosts either now or once it expires
+			Event::OpenTimerWindow(TimerTarget::Item(item_id)) => {
+				(item_keys(*item_id), false)
+			}
+			Event::OpenTimerWindow(TimerTarget::Host(key)) => {
+				(vec![*key], false)
+			}
+			Event::ApplyReplacements(replacements) => {
+				let keys = replacements
+					.iter()
+					.filter(|replacement| replacement.valid)
+					.flat_map(|replacement| match replacement.location {
+						ReplaceLocation::Ip(item_id) => item_keys(item_id),
+						ReplaceLocation::Host(item_id, host_id) => {
+							vec![(item_id, host_id)]
+						}
+						ReplaceLocation::Line(_) => vec![],
+					})
+					.collect();
+				(keys, false)
+			}
+			_ => return vec![],
+		};
+
+		let mut names: Vec<String> = vec![];
+		for key in keys {
+			let Some(host) = self.find_host(key) else {
+				continue;
+			};
+			if (!disabling || host.enabled)
+				&& self.is_protected(&host.name)
+				&& !names.contains(&host.name)
+			{
+				names.push(host.name.clone());
+			}
+		}
+		names
 	}
 
-	fn handle_parse_hosts_ok(&mut self, hosts_info: HostsInfo) {
-		self.hosts_info_draft.clone_from(&hosts_info);
-		self.hosts_info.clone_from(&hosts_info);
-		self.sys_hosts_loading = false;
+	fn find_host(&self, (item_id, host_id): HostKey) -> Option<&Host> {
+		self.hosts_info_draft
+			.list
+			.iter()
+			.find(|item| item.id == item_id)
+			.and_then(|item| item.get_host(host_id))
 	}
 
-	fn handle_os_err(&mut self, err: String) {
-		self.os_err = err;
-		self.sys_hosts_loading = false;
+	pub fn pending_protected_names(&self) -> &[String] {
+		self.pending_protected
+			.front()
+			.map_or(&[], |(_, names)| names.as_slice())
 	}
 
-	pub fn close_item_form_window(&mut self) {
+	pub fn confirm_protected(&mut self) {
 		self.opened_window = None;
-		self.item_form.reset();
+		if let Some((event, _)) = self.pending_protected.pop_front() {
+			self.flush_content_edit();
+			self.apply_event(event);
+		}
+		self.ask_next_protected();
 	}
 
-	pub fn new_item(&mut self) {
-		if !self.item_form.validate() {
-			return;
+	pub fn cancel_protected(&mut self) {
+		self.opened_window = None;
+		self.pending_protected.pop_front();
+		self.ask_next_protected();
+	}
+
+	/// Ask about the next event touching protected hosts, unless the last
+	/// one opened a window of its own.
+	fn ask_next_protected(&mut self) {
+		if !self.pending_protected.is_empty() && self.opened_window.is_none() {
+			self.set_opened_window(OpenedWindow::ConfirmProtected);
 		}
-		self.hosts_info_draft.add_item(&self.item_form);
-		self.update_content();
-		self.close_item_form_window();
 	}
 
-	pub fn save_hosts(&mut self) {
+	pub fn send_event(&self, event: Event) {
+		self.channel.send(event);
+	}
+
+	pub fn parse_sys_hosts(&mut self) {
+		self.parse_hosts(false);
+	}
+
+	fn parse_hosts(&mut self, elevated: bool) {
+		if self.hosts_path.to_string_lossy().is_empty() {
+			if let Some(hosts_path) = self.settings.custom_hosts_path() {
+				self.hosts_path = hosts_path;
+			} else {
+				let Ok(sys_hosts_path) = get_sys_hosts_path() else {
+					self.error = Some(HostsError::no_sys_hosts_path());
+					return;
+				};
+				self.hosts_path = sys_hosts_path;
+			}
+		}
+		self.read_only = false;
 		self.sys_hosts_loading = true;
 		let tx = self.channel.tx.clone();
-		let hosts_path = self.sys_hosts_path.clone();
-		let hosts_info = self.hosts_info_draft.clone();
-		thread::spawn(move || -> Result<()> {
-			if let Err(err) = hosts_info.save_to_file(hosts_path) {
-				tx.send(Event::OsErr(err.to_string()))?;
+		let hosts_path = self.hosts_path.clone();
+		let options = self.settings.hosts_options.clone();
+		thread::spawn(move || {
+			let result = if elevated {
+				HostsInfo::parse_from_file_elevated(hosts_path.clone(), options)
 			} else {
-				tx.send(Event::SaveHostsOk)?;
+				HostsInfo::parse_from_file(hosts_path.clone(), options)
+			};
+			match result {
+				Ok(hosts_info) => {
+					tx.send(Event::ParseHostsOk(hosts_path, hosts_info));
+				}
+				Err(err) => {
+					let error =
+						HostsError::new(FileOp::Read, &hosts_path, &err);
+					tx.send(Event::HostsErr(hosts_path, error));
+				}
 			}
-			Ok(())
 		});
 	}
 
-	pub fn reset_hosts(&mut self) {
-		self.hosts_info_draft.clone_from(&self.hosts_info);
+	/// Reload the hosts file and tell when it is done, asks first when the
+	/// draft has unsaved changes.
+	pub fn reload_hosts(&mut self) {
+		self.flush_content_edit();
+		if self.loaded && self.is_hosts_changed() {
+			self.confirm_discard(DiscardAction::Reload);
+			return;
+		}
+		self.notify_reload = true;
+		self.parse_sys_hosts();
 	}
 
-	pub fn is_hosts_changed(&self) -> bool {
-		self.hosts_info_draft.content != self.hosts_info.content
+	/// Whether the window can close now, otherwise asks the user first.
+	/// While a file is read or written the close waits for it to finish.
+	pub fn request_close(&mut self) -> bool {
+		self.flush_content_edit();
+		if self.any_loading() {
+			self.close_pending = true;
+			return false;
+		}
+		let dirty_tab = (0..self.tab_count()).find(|idx| self.tab_info(*idx).2);
+		let Some(idx) = dirty_tab else {
+			return true;
+		};
+		self.switch_tab(idx);
+		self.confirm_discard(DiscardAction::Close);
+		false
 	}
 
-	pub fn update_list(&mut self) {
-		self.hosts_info_draft.update_list();
+	/// Whether a confirmed close waits for the window to close.
+	pub fn take_close_requested(&mut self) -> bool {
+		std::mem::take(&mut self.close_requested)
 	}
 
-	pub fn update_content(&mut self) {
-		self.hosts_info_draft.update_content();
+	fn confirm_discard(&mut self, action: DiscardAction) {
+		self.pending_discard = Some(action);
+		self.set_opened_window(OpenedWindow::ConfirmDiscard);
 	}
 
-	pub fn set_opened_window(&mut self, window: OpenedWindow) {
-		self.opened_window = Some(window);
+	/// Save the draft, then go on with the pending action once it is saved.
+	pub fn confirm_save(&mut self) {
+		self.opened_window = None;
+		if let Some(DiscardAction::Reload) = self.pending_discard {
+			// saving reloads the file anyway
+			self.pending_discard = None;
+		}
+		self.save_hosts();
 	}
 
-	pub fn is_window_open(&self, window: OpenedWindow) -> bool {
-		if let Some(win) = &self.opened_window {
-			return *win == window;
+	pub fn confirm_discard_draft(&mut self) {
+		self.opened_window = None;
+		match self.pending_discard.take() {
+			Some(DiscardAction::Close) => {
+				// so that the discarded draft is not autosaved on exit
+				self.reset_hosts();
+				self.close_requested = self.request_close();
+			}
+			Some(DiscardAction::CloseTab) => {
+				self.remove_active_tab();
+			}
+			Some(DiscardAction::Reload) => {
+				self.reset_hosts();
+				self.reload_hosts();
+			}
+			Some(DiscardAction::ChangeHostsPath) => {
+				self.reset_hosts();
+				self.apply_settings();
+			}
+			None => {}
 		}
-		false
 	}
 
-	fn edit_item_ip(&mut self, item_id: usize, ip: String) {
-		if let Some(item) = self.hosts_info_draft.get_item_mut(item_id) {
-			if item.validate_ip(&ip) {
-				item.ip = ip;
-			}
+	pub fn cancel_discard(&mut self) {
+		self.opened_window = None;
+		// back to the settings that are not applied yet
+		if self.pending_discard.take() == Some(DiscardAction::ChangeHostsPath) {
+			self.set_opened_window(OpenedWindow::Settings);
 		}
-		self.update_content();
 	}
 
-	fn toggle_host_enable(&mut self, item_id: usize, host_id: usize) {
-		if let Some(item) = self.hosts_info_draft.get_item_mut(item_id) {
-			if let Some(host) = item.get_host_mut(host_id) {
-				host.enabled = !host.enabled;
+	fn handle_parse_hosts_ok(&mut self, hosts_info: HostsInfo) {
+		if std::mem::take(&mut self.notify_reload) {
+			let path = self.hosts_path.display();
+			if self.is_hosts_changed() {
+				let message = tf("toast.reloaded_discarded", &[&path]);
+				self.toasts.push(ToastLevel::Warning, message);
+			} else {
+				let message = tf("toast.reloaded", &[&path]);
+				self.toasts.push(ToastLevel::Success, message);
 			}
 		}
-		self.update_content();
+		self.hosts_info_draft.clone_from(&hosts_info);
+		self.hosts_info.clone_from(&hosts_info);
+		self.selection.clear();
+		self.sys_hosts_loading = false;
+		self.loaded = true;
+		self.error = None;
+		if self.is_system && !mem::replace(&mut self.restore_checked, true) {
+			self.check_autosave();
+		}
+		self.refresh_audit();
+		let found: Vec<(String, String)> = self
+			.findings
+			.iter()
+			.map(|finding| (finding.ip.clone(), finding.host.clone()))
+			.collect();
+		let notified = self
+			.notified_findings
+			.insert(self.hosts_path.clone(), found.clone());
+		if !found.is_empty() && notified != Some(found) {
+			let message = tf(
+				"toast.audit_findings",
+				&[&self.findings.len(), &self.hosts_path.display()],
+			);
+			self.toasts.push_action(
+				ToastLevel::Warning,
+				message,
+				ToastAction::ShowAudit,
+			);
+		}
 	}
 
-	fn delete_item(&mut self, item_id: usize) {
-		self.hosts_info_draft.remove_item(item_id);
-		self.update_content();
+	/// Audit the draft again if it changed since it was last audited.
+	pub fn refresh_audit(&mut self) {
+		let revision = self.hosts_info_draft.revision();
+		if self.audited_revision == Some(revision) {
+			return;
+		}
+		if self.audit_rules.is_none() {
+			let rules = AuditRules::load().unwrap_or_else(|err| {
+				let message = tf("toast.audit_rules_invalid", &[&err]);
+				self.toasts.push(ToastLevel::Error, message);
+				AuditRules::bundled()
+			});
+			self.audit_rules = Some(rules);
+		}
+		if let Some(rules) = &self.audit_rules {
+			self.findings = rules.audit(&self.hosts_info_draft.list);
+		}
+		self.audited_revision = Some(revision);
 	}
 
-	fn delete_host(&mut self, item_id: usize, host_id: usize) {
-		let Some(item) = self.hosts_info_draft.get_item_mut(item_id) else {
+	/// Lint the draft again if it or the lint platform changed.
+	pub fn refresh_lints(&mut self) {
+		let key = (
+			self.hosts_info_draft.revision(),
+			self.settings.lint_platform(),
+		);
+		if self.linted == Some(key) {
 			return;
-		};
-		item.remove_host(host_id);
-		if item.hosts.is_empty() {
-			self.hosts_info_draft.remove_item(item_id);
 		}
-		self.update_content();
+		let draft = &self.hosts_info_draft;
+		self.lints = lint(&draft.content, &draft.options, key.1);
+		self.linted = Some(key);
 	}
 
-	fn open_add_hosts_window(&mut self, item_id: usize) {
-		self.selected_item_id = Some(item_id);
-		self.set_opened_window(OpenedWindow::AddHosts);
+	fn fix_lint(&mut self, lint: Lint) {
+		self.refresh_lints();
+		// the content may have changed since the fix was offered
+		if !self.lints.contains(&lint) {
+			return;
+		}
+		let draft = &mut self.hosts_info_draft;
+		draft.content =
+			apply_fix(&draft.content, draft.file_format.eol.as_str(), &lint);
+		self.update_list();
 	}
 
-	pub fn close_add_hosts_window(&mut self) {
-		self.selected_item_id = None;
-		self.close_item_form_window();
+	pub fn audit_rules(&self) -> Option<&AuditRules> {
+		self.audit_rules.as_ref()
 	}
 
-	pub fn add_hosts(&mut self) {
-		if !self.item_form.validate_hosts() {
+	pub fn open_audit_window(&mut self) {
+		self.refresh_audit();
+		self.set_opened_window(OpenedWindow::Audit);
+	}
+
+	pub fn close_audit_window(&mut self) {
+		self.opened_window = None;
+	}
+
+	pub fn disable_findings(&mut self, keys: Vec<HostKey>) {
+		self.send_event(Event::ToggleHostsEnable(keys, false));
+	}
+
+	pub fn import_audit_rules(&mut self) {
+		let Some(path) = self.path_form.validate() else {
 			return;
+		};
+		match AuditRules::import(&path) {
+			Ok(rules) => {
+				let message =
+					tf("toast.audit_rules_imported", &[&rules.version]);
+				self.toasts.push(ToastLevel::Success, message);
+				self.audit_rules = Some(rules);
+				self.audited_revision = None;
+				self.open_audit_window();
+			}
+			Err(err) => {
+				self.path_form.path_error = err.to_string();
+			}
 		}
-		let Some(item_id) = self.selected_item_id else {
+	}
+
+	/// Offer the draft left by the last run if it differs from the file.
+	fn check_autosave(&mut self) {
+		let Some(autosave) = Autosave::load() else {
 			return;
 		};
-		let Some(item) = self.hosts_info_draft.get_item_mut(item_id) else {
+		if autosave.hosts_path != self.hosts_path {
 			return;
-		};
-		item.add_hosts(self.item_form.hosts.to_split_whitespace_vec(), true);
-		self.close_add_hosts_window();
-		self.update_content();
+		}
+		self.has_autosave = true;
+		if autosave.content == self.hosts_info.content {
+			return;
+		}
+		self.diff = diff_hunks(&self.hosts_info.content, &autosave.content);
+		self.restore = Some(autosave);
+		self.set_opened_window(OpenedWindow::RestoreDraft);
 	}
 
-	fn open_edit_host_window(&mut self, item_id: usize, host_id: usize) {
-		self.selected_item_id = Some(item_id);
-		self.selected_host_id = Some(host_id);
-		if let Some(item) = self.hosts_info_draft.get_item_mut(item_id) {
-			if let Some(host) = item.get_host_mut(host_id) {
-				self.item_form.hosts = host.name.clone();
-			};
+	pub fn restore_draft(&mut self) {
+		let Some(autosave) = self.restore.take() else {
+			return;
 		};
-		self.set_opened_window(OpenedWindow::EditHost);
+		self.close_diff_window();
+		self.reset_hosts();
+		self.hosts_info_draft.content = autosave.content;
+		self.hosts_info_draft.update_list();
 	}
 
-	pub fn close_edit_host_window(&mut self) {
+	pub fn discard_autosave(&mut self) {
+		self.restore = None;
+		self.close_diff_window();
+		self.remove_autosave();
+	}
+
+	/// Write the changed draft to disk at most every [`AUTOSAVE_INTERVAL`],
+	/// returns when to check again.
+	pub fn tick_autosave(&mut self) -> Option<Duration> {
+		// only the system hosts file is autosaved, the other tabs are files
+		// the user chose to open
+		if !self.is_system
+			|| !self.loaded
+			|| self.sys_hosts_loading
+			|| self.restore.is_some()
+		{
+			return None;
+		}
+		if !self.is_hosts_changed() {
+			self.remove_autosave();
+			return None;
+		}
+		if self.autosaved_revision == Some(self.hosts_info_draft.revision()) {
+			return None;
+		}
+		if let Some(elapsed) = self.autosaved_at.map(|at| at.elapsed()) {
+			if elapsed < AUTOSAVE_INTERVAL {
+				return Some(AUTOSAVE_INTERVAL - elapsed);
+			}
+		}
+		self.autosave();
+		None
+	}
+
+	/// Autosave a changed draft right away, for when the app exits.
+	pub fn autosave_on_exit(&mut self) {
+		self.flush_content_edit();
+		if !self.tabs.is_empty() {
+			self.activate_tab(0);
+		}
+		if !self.loaded || self.restore.is_some() {
+			return;
+		}
+		if self.is_hosts_changed() {
+			self.autosave();
+		} else {
+			self.remove_autosave();
+		}
+	}
+
+	fn autosave(&mut self) {
+		let autosave = Autosave::new(
+			self.hosts_path.clone(),
+			self.hosts_info_draft.content.clone(),
+		);
+		// a failed autosave is tried again after the interval
+		self.autosaved_at = Some(Instant::now());
+		if autosave.save().is_ok() {
+			self.has_autosave = true;
+			self.autosaved_revision = Some(self.hosts_info_draft.revision());
+		}
+	}
+
+	fn remove_autosave(&mut self) {
+		self.autosaved_revision = None;
+		if std::mem::take(&mut self.has_autosave) {
+			let _ = Autosave::remove();
+		}
+	}
+
+	fn handle_hosts_err(&mut self, error: HostsError) {
+		self.toasts.push(ToastLevel::Error, error.title());
+		self.error = Some(error);
+		self.sys_hosts_loading = false;
+		self.saving = None;
+		self.notify_reload = false;
+		self.pending_discard = None;
+	}
+
+	/// Take one of the ways out of the current error.
+	pub fn recover(&mut self, recovery: Recovery) {
+		let Some(error) = self.error.take() else {
+			return;
+		};
+		match (recovery, error.op) {
+			(Recovery::Retry, FileOp::Read) => {
+				self.parse_hosts(false);
+			}
+			(Recovery::RequestElevation, FileOp::Read) => {
+				self.parse_hosts(true);
+			}
+			(Recovery::Retry, FileOp::Save) => {
+				self.write_hosts(false);
+			}
+			(Recovery::RequestElevation, FileOp::Save) => {
+				self.write_hosts(true);
+			}
+			(Recovery::OpenReadOnly, _) => {
+				self.read_only = true;
+			}
+			(Recovery::ChooseFile, _) => {
+				self.open_path_window(OpenedWindow::OpenFile);
+			}
+			(Recovery::SaveDraftAs, _) | (_, FileOp::SaveAs) => {
+				self.open_path_window(OpenedWindow::SaveAs);
+			}
+		}
+	}
+
+	/// The recoveries of `error` that apply to the active tab, files other
+	/// than the system hosts file are saved without elevation.
+	pub fn recoveries(&self, error: &HostsError) -> Vec<Recovery> {
+		error
+			.recoveries()
+			.into_iter()
+			.filter(|recovery| {
+				self.is_system || *recovery != Recovery::RequestElevation
+			})
+			.collect()
+	}
+
+	pub fn dismiss_error(&mut self) {
+		self.error = None;
+	}
+
+	pub fn open_path_window(&mut self, window: OpenedWindow) {
+		self.path_form.reset();
+		self.set_opened_window(window);
+	}
+
+	pub fn close_path_window(&mut self) {
+		self.opened_window = None;
+		self.path_form.reset();
+	}
+
+	pub fn open_file(&mut self) {
+		let Some(path) = self.path_form.validate() else {
+			return;
+		};
+		self.close_path_window();
+		self.open_path(path);
+	}
+
+	/// Read the file of the active tab again from `path`, dropping its
+	/// state.
+	fn reset_active_file(&mut self, path: PathBuf) {
+		self.hosts_path = path;
+		self.error = None;
+		self.loaded = false;
+		self.content_edited_at = None;
+		self.hosts_info = HostsInfo::default();
+		self.hosts_info_draft = HostsInfo::default();
+		self.selection.clear();
+		self.parse_sys_hosts();
+	}
+
+	/// Write the draft to another file, the hosts file stays as it is.
+	pub fn save_as(&mut self) {
+		let Some(path) = self.path_form.validate() else {
+			return;
+		};
+		self.close_path_window();
+		self.flush_content_edit();
+		self.sys_hosts_loading = true;
+		let tx = self.channel.tx.clone();
+		let hosts_path = self.hosts_path.clone();
+		let hosts_info = self.hosts_info_draft.clone();
+		thread::spawn(move || {
+			if let Err(err) = hosts_info.write_to_file(&path) {
+				let error = HostsError::new(FileOp::SaveAs, &path, &err);
+				tx.send(Event::HostsErr(hosts_path, error));
+			} else {
+				tx.send(Event::SaveAsOk(hosts_path, path));
+			}
+		});
+	}
+
+	pub fn close_item_form_window(&mut self) {
+		self.opened_window = None;
+		self.item_form.reset();
+	}
+
+	pub fn new_item(&mut self) {
+		if !self.item_form.validate() {
+			return;
+		}
+		self.hosts_info_draft.add_item(&self.item_form);
+		self.update_content();
+		self.close_item_form_window();
+	}
+
+	pub fn save_hosts(&mut self) {
+		self.write_hosts(false);
+	}
+
+	/// A failed save leaves the draft as it is, so it can be saved again.
+	fn write_hosts(&mut self, elevated: bool) {
+		self.error = None;
+		self.sys_hosts_loading = true;
+		self.saving = Some(SavedChange {
+			path: self.hosts_path.clone(),
+			before: self.hosts_info.clone(),
+			after: self.hosts_info_draft.content.clone(),
+		});
+		let tx = self.channel.tx.clone();
+		let hosts_path = self.hosts_path.clone();
+		let hosts_info = self.hosts_info_draft.clone();
+		thread::spawn(move || {
+			let result = if elevated {
+				hosts_info.save_to_file_elevated(hosts_path.clone())
+			} else {
+				hosts_info.save_to_file(hosts_path.clone())
+			};
+			if let Err(err) = result {
+				let error = HostsError::new(FileOp::Save, &hosts_path, &err);
+				tx.send(Event::HostsErr(hosts_path, error));
+			} else {
+				tx.send(Event::SaveHostsOk(hosts_path));
+			}
+		});
+	}
+
+	fn handle_save_hosts_ok(&mut self) {
+		if let Some(change) = self.saving.take() {
+			let count =
+				count_changed_lines(&change.before.content, &change.after);
+			let message =
+				tf("toast.saved", &[&count, &self.hosts_path.display()]);
+			self.toasts
+				.push_change(ToastLevel::Success, message, Some(change));
+		}
+		match self.pending_discard.take() {
+			Some(DiscardAction::Close) => {
+				self.sys_hosts_loading = false;
+				self.hosts_info.clone_from(&self.hosts_info_draft);
+				self.close_requested = self.request_close();
+				return;
+			}
+			Some(DiscardAction::CloseTab) => {
+				self.sys_hosts_loading = false;
+				self.remove_active_tab();
+				return;
+			}
+			Some(DiscardAction::ChangeHostsPath) => {
+				self.sys_hosts_loading = false;
+				self.hosts_info.clone_from(&self.hosts_info_draft);
+				self.apply_settings();
+				return;
+			}
+			_ => {}
+		}
+		self.parse_sys_hosts();
+	}
+
+	/// Whether `change` can be undone, which is only done in its own tab
+	/// and would drop the unsaved changes otherwise. Only the last save of
+	/// the file can be undone, older ones would drop the saves after them.
+	pub fn can_undo_save(&self, change: &SavedChange) -> bool {
+		change.path == self.hosts_path
+			&& change.after == self.hosts_info.content
+			&& !self.is_hosts_changed()
+			&& self.can_save_file()
+	}
+
+	fn can_save_file(&self) -> bool {
+		self.loaded && !self.read_only && !self.sys_hosts_loading
+	}
+
+	pub fn run_toast_action(&mut self, toast_id: usize, action: ToastAction) {
+		if action == ToastAction::ShowAudit {
+			self.toasts.dismiss(toast_id);
+			self.open_audit_window();
+			return;
+		}
+		let Some(change) = self
+			.toasts
+			.get(toast_id)
+			.and_then(|toast| toast.change.clone())
+		else {
+			return;
+		};
+		match action {
+			ToastAction::Undo => {
+				if !self.can_undo_save(&change) {
+					return;
+				}
+				self.toasts.dismiss(toast_id);
+				self.hosts_info_draft.clone_from(&change.before);
+				self.save_hosts();
+			}
+			ToastAction::ShowDiff => {
+				self.diff = diff_hunks(&change.before.content, &change.after);
+				self.set_opened_window(OpenedWindow::Diff);
+			}
+			ToastAction::ShowAudit => {}
+		}
+	}
+
+	pub fn close_diff_window(&mut self) {
+		self.opened_window = None;
+		self.diff.clear();
+	}
+
+	pub fn open_normalize_window(&mut self) {
+		self.set_opened_window(OpenedWindow::Normalize);
+		self.preview_normalize();
+	}
+
+	/// Normalize the draft with the chosen steps into the diff, without
+	/// changing it yet.
+	pub fn preview_normalize(&mut self) {
+		let draft = &self.hosts_info_draft;
+		let normalized = draft.normalized(&self.normalize_steps);
+		self.diff = diff_hunks(&draft.content, &normalized);
+		self.normalized = Some(normalized);
+	}
+
+	pub fn apply_normalize(&mut self) {
+		if let Some(content) = self.normalized.take() {
+			self.hosts_info_draft.content = content;
+			self.update_list();
+		}
+		self.close_normalize_window();
+	}
+
+	pub fn close_normalize_window(&mut self) {
+		self.normalized = None;
+		self.close_diff_window();
+	}
+
+	pub fn reset_hosts(&mut self) {
+		self.content_edited_at = None;
+		self.hosts_info_draft.clone_from(&self.hosts_info);
+		self.selection.clear();
+	}
+
+	pub fn is_hosts_changed(&self) -> bool {
+		self.hosts_info_draft.content != self.hosts_info.content
+	}
+
+	pub fn can_save(&self) -> bool {
+		self.can_save_file() && self.is_hosts_changed()
+	}
+
+	pub fn update_list(&mut self) {
+		self.content_edited_at = None;
+		self.hosts_info_draft.update_list_by_edit();
+		self.selection.clear();
+	}
+
+	pub fn update_content(&mut self) {
+		self.flush_content_edit();
+		self.hosts_info_draft.update_content();
+	}
+
+	/// Note an edit in the text view, the list follows once typing pauses.
+	pub fn edit_content(&mut self) {
+		self.content_edited_at = Some(Instant::now());
+	}
+
+	/// Update the list from the edited text once typing pauses, returns when
+	/// to check again.
+	pub fn tick_content_edit(&mut self) -> Option<Duration> {
+		let elapsed = self.content_edited_at?.elapsed();
+		if elapsed < UPDATE_LIST_DELAY {
+			return Some(UPDATE_LIST_DELAY - elapsed);
+		}
+		self.update_list();
+		None
+	}
+
+	/// Whether the list lags behind a text edit.
+	pub fn is_content_edit_pending(&self) -> bool {
+		self.content_edited_at.is_some()
+	}
+
+	/// Apply a pending text edit to the list right away, before the list is
+	/// read or changed.
+	fn flush_content_edit(&mut self) {
+		if self.content_edited_at.is_some() {
+			self.update_list();
+		}
+	}
+
+	pub fn set_opened_window(&mut self, window: OpenedWindow) {
+		self.flush_content_edit();
+		self.opened_window = Some(window);
+	}
+
+	pub fn is_window_open(&self, window: OpenedWindow) -> bool {
+		if let Some(win) = &self.opened_window {
+			return *win == window;
+		}
+		false
+	}
+
+	fn edit_item_ip(&mut self, item_id: usize, ip: String) {
+		if let Some(item) = self.hosts_info_draft.get_item_mut(item_id) {
+			if item.validate_ip(&ip) {
+				item.ip = ip;
+			}
+		}
+		self.update_content();
+	}
+
+	fn toggle_host_enable(&mut self, item_id: usize, host_id: usize) {
+		if let Some(item) = self.hosts_info_draft.get_item_mut(item_id) {
+			if let Some(host) = item.get_host_mut(host_id) {
+				host.enabled = !host.enabled;
+			}
+		}
+		self.update_content();
+	}
+
+	fn delete_item(&mut self, item_id: usize) {
+		self.hosts_info_draft.remove_item(item_id);
+		self.update_content();
+	}
+
+	fn delete_host(&mut self, item_id: usize, host_id: usize) {
+		let Some(item) = self.hosts_info_draft.get_item_mut(item_id) else {
+			return;
+		};
+		item.remove_host(host_id);
+		if item.hosts.is_empty() {
+			self.hosts_info_draft.remove_item(item_id);
+		}
+		self.update_content();
+	}
+
+	fn rename_host(&mut self, item_id: usize, host_id: usize, name: String) {
+		if let Some(item) = self.hosts_info_draft.get_item_mut(item_id) {
+			item.rename_host(host_id, name);
+		}
+		self.update_content();
+	}
+
+	fn open_add_hosts_window(&mut self, item_id: usize) {
+		self.selected_item_id = Some(item_id);
+		self.set_opened_window(OpenedWindow::AddHosts);
+	}
+
+	pub fn close_add_hosts_window(&mut self) {
+		self.selected_item_id = None;
+		self.close_item_form_window();
+	}
+
+	pub fn add_hosts(&mut self) {
+		if !self.item_form.validate_hosts() {
+			return;
+		}
+		let Some(item_id) = self.selected_item_id else {
+			return;
+		};
+		let Some(item) = self.hosts_info_draft.get_item_mut(item_id) else {
+			return;
+		};
+		item.add_hosts(self.item_form.hosts.to_split_whitespace_vec(), true);
+		self.close_add_hosts_window();
+		self.update_content();
+	}
+
+	fn open_edit_host_window(&mut self, item_id: usize, host_id: usize) {
+		self.selected_item_id = Some(item_id);
+		self.selected_host_id = Some(host_id);
+		if let Some(item) = self.hosts_info_draft.get_item_mut(item_id) {
+			if let Some(host) = item.get_host_mut(host_id) {
+				self.item_form.hosts = host.name.clone();
+			};
+		};
+		self.set_opened_window(OpenedWindow::EditHost);
+	}
+
+	pub fn close_edit_host_window(&mut self) {
 		self.selected_item_id = None;
 		self.selected_host_id = None;
 		self.close_item_form_window();
@@ -267,12 +1322,10 @@ impl Hed {
 		else {
 			return;
 		};
-		let Some(item) = self.hosts_info_draft.get_item_mut(item_id) else {
-			return;
-		};
-		item.rename_host(host_id, self.item_form.hosts.clone());
+		let name = self.item_form.hosts.clone();
 		self.close_edit_host_window();
-		self.update_content();
+		// through an event, so that renaming a protected host is confirmed
+		self.send_event(Event::RenameHost(item_id, host_id, name));
 	}
 
 	fn toggle_all_host_enable(&mut self, item_id: usize, enabled: bool) {
@@ -283,4 +1336,1032 @@ impl Hed {
 		}
 		self.update_content();
 	}
+
+	pub fn open_find_replace_window(&mut self, target: ReplaceTarget) {
+		self.find_replace.target = target;
+		self.set_opened_window(OpenedWindow::FindReplace);
+	}
+
+	pub fn close_find_replace_window(&mut self) {
+		self.opened_window = None;
+		self.find_replace.reset();
+	}
+
+	pub fn find_replace_preview(&mut self) -> Vec<Replacement> {
+		match self.find_replace.preview(&self.hosts_info_draft) {
+			Ok(replacements) => {
+				self.find_replace.find_error.clear();
+				replacements
+			}
+			Err(err) => {
+				self.find_replace.find_error = err.to_string();
+				vec![]
+			}
+		}
+	}
+
+	pub fn apply_find_replace(&mut self) {
+		let replacements = self.find_replace_preview();
+		if !self.find_replace.find_error.is_empty() {
+			return;
+		}
+		self.close_find_replace_window();
+		self.send_event(Event::ApplyReplacements(replacements));
+	}
+
+	/// Hosts in the order they are shown in the options view.
+	pub fn visible_host_keys(&self) -> Vec<HostKey> {
+		self.hosts_info_draft
+			.list
+			.iter()
+			.filter(|item| item.contains(&self.search_ip_hosts))
+			.flat_map(|item| item.hosts.iter().map(|host| (item.id, host.id)))
+			.collect()
+	}
+
+	pub fn selected_host_keys(&self) -> Vec<HostKey> {
+		self.selection.to_vec(&self.hosts_info_draft.host_keys())
+	}
+
+	fn select_host(&mut self, key: HostKey, mode: SelectMode) {
+		match mode {
+			SelectMode::Toggle => {
+				self.selection.toggle(key);
+			}
+			SelectMode::Range => {
+				let order = self.visible_host_keys();
+				self.selection.select_range(&order, key);
+			}
+		}
+	}
+
+	fn toggle_hosts_enable(&mut self, keys: Vec<HostKey>, enabled: bool) {
+		for (item_id, host_id) in keys {
+			if let Some(item) = self.hosts_info_draft.get_item_mut(item_id) {
+				if let Some(host) = item.get_host_mut(host_id) {
+					host.enabled = enabled;
+				}
+			}
+		}
+		self.update_content();
+	}
+
+	fn delete_hosts(&mut self, keys: Vec<HostKey>) {
+		self.hosts_info_draft.remove_hosts(&keys);
+		self.selection.clear();
+		self.update_content();
+	}
+
+	fn transfer_hosts(&mut self, keys: Vec<HostKey>, ip: String, remove: bool) {
+		self.hosts_info_draft.transfer_hosts(&keys, &ip, remove);
+		self.selection.clear();
+		self.update_content();
+	}
+
+	pub fn transfer_selected_hosts(&mut self) {
+		if !self.item_form.validate_ip() {
+			return;
+		}
+		let remove = self.is_window_open(OpenedWindow::MoveHosts);
+		let keys = self.selected_host_keys();
+		self.transfer_hosts(keys, self.item_form.ip.clone(), remove);
+		self.close_item_form_window();
+	}
+
+	fn move_item(&mut self, item_id: usize, to: usize) {
+		self.hosts_info_draft.move_item(item_id, to);
+		self.hosts_info_draft.update_content_by_order();
+	}
+
+	fn move_host(&mut self, key: HostKey, to_item_id: usize, to: usize) {
+		self.hosts_info_draft.move_host(key, to_item_id, to);
+		self.hosts_info_draft.update_content_by_order();
+	}
+
+	fn set_hosts_options(&mut self, options: HostsOptions) {
+		if options == self.settings.hosts_options {
+			return;
+		}
+		let reparse = options.parse_commented
+			!= self.settings.hosts_options.parse_commented;
+		self.flush_content_edit();
+		self.settings.hosts_options.clone_from(&options);
+
+		// the format applies to the next edit, the drafts stay as they are
+		let active = (
+			&mut self.hosts_info,
+			&mut self.hosts_info_draft,
+			&mut self.selection,
+		);
+		let parked = self.tabs.iter_mut().map(|tab| {
+			(
+				&mut tab.hosts_info,
+				&mut tab.hosts_info_draft,
+				&mut tab.selection,
+			)
+		});
+		for (hosts_info, draft, selection) in iter::once(active).chain(parked) {
+			hosts_info.options.clone_from(&options);
+			draft.options.clone_from(&options);
+			if reparse {
+				hosts_info.update_list();
+				draft.update_list();
+				selection.clear();
+			}
+		}
+	}
+
+	pub fn save_settings(&mut self) {
+		if let Err(err) = self.settings.save() {
+			let message = tf("toast.settings_save_failed", &[&err]);
+			self.toasts.push(ToastLevel::Error, message);
+		}
+	}
+
+	pub fn take_style_changed(&mut self) -> bool {
+		std::mem::take(&mut self.style_changed)
+	}
+
+	pub fn take_loaded_fonts(&mut self) -> Option<LoadedFonts> {
+		self.loaded_fonts.take()
+	}
+
+	/// Read the fonts on a worker thread, looking for system fonts can take
+	/// a while.
+	fn load_fonts(&self) {
+		let tx = self.channel.tx.clone();
+		let custom_path = self.settings.custom_font_path();
+		thread::spawn(move || {
+			tx.send(Event::FontsLoaded(LoadedFonts::load(custom_path)));
+		});
+	}
+
+	fn handle_fonts_loaded(&mut self, fonts: LoadedFonts) {
+		// the font was changed again while these were read
+		if fonts.custom_path != self.settings.custom_font_path() {
+			return;
+		}
+		if let (Some(path), Some(Err(err))) =
+			(&fonts.custom_path, &fonts.custom)
+		{
+			let message = tf("toast.font_load_failed", &[&path.display(), err]);
+			self.toasts.push(ToastLevel::Error, message);
+		}
+		self.loaded_fonts = Some(fonts);
+	}
+
+	pub fn set_theme(&mut self, theme: Theme) {
+		if self.settings.theme != theme {
+			self.settings.theme = theme;
+			self.style_changed = true;
+			self.save_settings();
+		}
+	}
+
+	pub fn open_settings_window(&mut self) {
+		self.settings_draft.clone_from(&self.settings);
+		self.recording_shortcut = None;
+		self.set_opened_window(OpenedWindow::Settings);
+	}
+
+	pub fn close_settings_window(&mut self) {
+		self.opened_window = None;
+	}
+
+	pub fn apply_settings(&mut self) {
+		if self.settings_draft.keymap.has_conflict() {
+			return;
+		}
+		let settings = self.settings_draft.clone();
+		let hosts_path_changed =
+			settings.hosts_path != self.settings.hosts_path;
+		self.flush_content_edit();
+		if hosts_path_changed && self.tab_info(0).2 {
+			// the draft of the system hosts file would be dropped
+			self.switch_tab(0);
+			self.confirm_discard(DiscardAction::ChangeHostsPath);
+			return;
+		}
+		self.style_changed |= settings.theme != self.settings.theme
+			|| settings.font_size != self.settings.font_size;
+		let font_changed = settings.font_path != self.settings.font_path;
+		set_lang(settings.lang());
+		self.set_hosts_options(settings.hosts_options.clone());
+		if settings.protected_hosts != self.settings.protected_hosts {
+			self.protected_hosts =
+				ProtectedHosts::with_system_hostname(&settings.protected_hosts);
+		}
+		self.settings = settings;
+		self.save_settings();
+		if font_changed {
+			self.load_fonts();
+		}
+		self.close_settings_window();
+		if hosts_path_changed && !self.tabs.is_empty() {
+			self.activate_tab(0);
+			self.reset_active_file(PathBuf::new());
+		} else if hosts_path_changed {
+			self.reset_active_file(PathBuf::new());
+		}
+	}
+
+	fn open_timer_window(&mut self, target: TimerTarget) {
+		let any_enabled = self
+			.timer_target_hosts(target)
+			.any(|(_, _, enabled)| enabled);
+		self.timer_form.reset();
+		self.timer_form.enable = !any_enabled;
+		self.timer_form.target = Some(target);
+		self.set_opened_window(OpenedWindow::Timer);
+	}
+
+	pub fn close_timer_window(&mut self) {
+		self.opened_window = None;
+		self.timer_form.reset();
+	}
+
+	/// The ip, name and state of the hosts a timer is set for.
+	fn timer_target_hosts(
+		&self,
+		target: TimerTarget,
+	) -> impl Iterator<Item = (&str, &str, bool)> {
+		self.hosts_info_draft
+			.list
+			.iter()
+			.flat_map(|item| item.hosts.iter().map(move |host| (item, host)))
+			.filter(move |(item, host)| match target {
+				TimerTarget::Item(item_id) => item.id == item_id,
+				TimerTarget::Host(key) => (item.id, host.id) == key,
+			})
+			.map(|(item, host)| {
+				(item.ip.as_str(), host.name.as_str(), host.enabled)
+			})
+	}
+
+	/// Switch the target hosts to the chosen state until the expiry, when
+	/// they go back to the state they had before.
+	pub fn apply_timer(&mut self) {
+		let Some(target) = self.timer_form.target else {
+			return;
+		};
+		let Some(expires_at) = self.timer_form.expires_at() else {
+			return;
+		};
+
+		let timers: Vec<HostTimer> = self
+			.timer_target_hosts(target)
+			.map(|(ip, host, enabled)| HostTimer {
+				ip: ip.to_string(),
+				host: host.to_string(),
+				expires_at,
+				revert_to: enabled,
+			})
+			.collect();
+
+		let enable = self.timer_form.enable;
+		for item in &mut self.hosts_info_draft.list {
+			for host in &mut item.hosts {
+				if timers
+					.iter()
+					.any(|timer| timer.is_for(&item.ip, &host.name))
+				{
+					host.enabled = enable;
+				}
+			}
+		}
+		for timer in timers {
+			self.hosts_info_draft.set_timer(timer);
+		}
+
+		self.close_timer_window();
+		self.update_content();
+	}
+
+	fn cancel_timer(&mut self, (item_id, host_id): HostKey) {
+		let Some(item) = self
+			.hosts_info_draft
+			.list
+			.iter()
+			.find(|item| item.id == item_id)
+		else {
+			return;
+		};
+		let Some(host) = item.get_host(host_id) else {
+			return;
+		};
+		let (ip, name) = (item.ip.clone(), host.name.clone());
+		self.hosts_info_draft.remove_timer(&ip, &name);
+		self.update_content();
+	}
+
+	/// Revert the hosts whose timer is up in every tab, returns when to
+	/// check again.
+	///
+	/// Timers of the saved files are written back right away, so that the
+	/// revert doesn't wait for the user to save.
+	pub fn tick_timers(&mut self) -> Option<Duration> {
+		let now = now_secs();
+		let active = self.hosts_path.clone();
+		let paths: Vec<PathBuf> = (0..self.tab_count())
+			.map(|idx| self.tab_info(idx).0.to_path_buf())
+			.collect();
+
+		let mut has_timers = false;
+		for path in paths {
+			// only the active tab can have a pending text edit
+			let edit_pending =
+				path == active && self.content_edited_at.is_some();
+			self.in_tab(&path, |hed| {
+				has_timers |= hed.tick_tab_timers(now, edit_pending);
+			});
+		}
+
+		has_timers.then_some(Duration::from_secs(1))
+	}
+
+	/// Revert the timers of the active tab that are up, returns whether it
+	/// has timers left.
+	fn tick_tab_timers(&mut self, now: u64, edit_pending: bool) -> bool {
+		if self.sys_hosts_loading || !self.loaded {
+			return false;
+		}
+
+		// the list is behind a pending text edit, expire once it caught up
+		let draft_expired =
+			!edit_pending && self.hosts_info_draft.expire_timers(now);
+		if self.hosts_info.has_expired_timers(now) {
+			if self.error.is_none() && !self.read_only {
+				self.save_expired_timers(now);
+			} else if draft_expired {
+				// reverted in the draft only, until the user saves it
+				let message = tf(
+					"toast.timers_unwritable",
+					&[&self.hosts_path.display()],
+				);
+				self.toasts.push(ToastLevel::Warning, message);
+			}
+		}
+
+		self.hosts_info.timers().next().is_some()
+			|| self.hosts_info_draft.timers().next().is_some()
+	}
+
+	/// The file is only taken as reverted once it was written.
+	fn save_expired_timers(&mut self, now: u64) {
+		let mut hosts_info = self.hosts_info.clone();
+		hosts_info.expire_timers(now);
+		self.sys_hosts_loading = true;
+		let tx = self.channel.tx.clone();
+		let hosts_path = self.hosts_path.clone();
+		thread::spawn(move || {
+			if let Err(err) = hosts_info.save_to_file(hosts_path.clone()) {
+				let error = HostsError::new(FileOp::Save, &hosts_path, &err);
+				tx.send(Event::HostsErr(hosts_path, error));
+			} else {
+				tx.send(Event::ExpiredTimersSaved(hosts_path, hosts_info));
+			}
+		});
+	}
+
+	pub fn open_command_palette(&mut self) {
+		self.command_palette.reset();
+		self.set_opened_window(OpenedWindow::CommandPalette);
+	}
+
+	pub fn close_command_palette(&mut self) {
+		self.opened_window = None;
+		self.command_palette.reset();
+	}
+
+	pub fn commands(&self) -> Vec<CommandEntry> {
+		let mut entries: Vec<CommandEntry> = Command::BINDABLE
+			.into_iter()
+			.filter(|command| *command != Command::OpenCommandPalette)
+			.map(|command| CommandEntry::new(command.clone(), command.label()))
+			.collect();
+
+		for item in &self.hosts_info_draft.list {
+			entries.push(CommandEntry::new(
+				Command::JumpToItem(item.id),
+				tf("command.go_to_item", &[&item.ip]),
+			));
+			for host in &item.hosts {
+				let key = if host.enabled {
+					"command.disable_host"
+				} else {
+					"command.enable_host"
+				};
+				entries.push(CommandEntry::new(
+					Command::ToggleHost((item.id, host.id)),
+					tf(key, &[&host.name, &item.ip]),
+				));
+			}
+		}
+
+		entries
+	}
+
+	pub fn run_command(&mut self, command: Command) {
+		self.close_command_palette();
+		self.flush_content_edit();
+
+		match command {
+			Command::Save => {
+				if self.can_save() {
+					self.save_hosts();
+				}
+			}
+			Command::Reset => {
+				self.reset_hosts();
+			}
+			Command::Reload => {
+				self.reload_hosts();
+			}
+			Command::OpenFile => {
+				self.open_path_window(OpenedWindow::OpenFile);
+			}
+			Command::SaveAs => {
+				if self.loaded {
+					self.open_path_window(OpenedWindow::SaveAs);
+				}
+			}
+			Command::CloseTab => {
+				self.close_tab(self.active_tab);
+			}
+			Command::Format => {
+				self.update_content();
+			}
+			Command::Normalize => {
+				if self.loaded {
+					self.open_normalize_window();
+				}
+			}
+			Command::NewItem => {
+				self.set_opened_window(OpenedWindow::NewItem);
+			}
+			Command::FindReplace => {
+				let target = match self.settings.view_kind {
+					ViewKind::Options => ReplaceTarget::Ip,
+					ViewKind::Text => ReplaceTarget::Text,
+				};
+				self.open_find_replace_window(target);
+			}
+			Command::OpenSettings => {
+				self.open_settings_window();
+			}
+			Command::OpenCommandPalette => {
+				self.open_command_palette();
+			}
+			Command::Audit => {
+				if self.loaded {
+					self.open_audit_window();
+				}
+			}
+			Command::SwitchView(view_kind) => {
+				self.settings.view_kind = view_kind;
+				self.save_settings();
+			}
+			Command::ToggleHost((item_id, host_id)) => {
+				self.send_event(Event::ToggleHostEnable(item_id, host_id));
+			}
+			Command::JumpToItem(item_id) => {
+				self.settings.view_kind = ViewKind::Options;
+				self.search_ip_hosts.clear();
+				self.scroll_to_item = Some(item_id);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		io,
+		path::{Path, PathBuf},
+		thread,
+	};
+
+	use anyhow::anyhow;
+	use rstest::rstest;
+
+	use super::{
+		Autosave, Command, DiscardAction, Event, FileOp, FileTab, Hed,
+		HostsError, HostsInfo, LoadedFonts, OpenedWindow, ReplaceTarget,
+		SavedChange, SelectMode, TimerTarget,
+	};
+	use crate::core::{lint::LintRule, RemovedHost, Separator, ToastLevel};
+
+	fn hed_with_content(content: &str) -> Hed {
+		let mut hed = Hed::default();
+		hed.hosts_info_draft.content = content.to_string();
+		hed.hosts_info_draft.update_list();
+		hed
+	}
+
+	#[test]
+	fn test_handle_events_in_order() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.send_event(Event::EditItemIp(0, "10.0.0.1".to_string()));
+		hed.send_event(Event::EditItemIp(0, "10.0.0.2".to_string()));
+		hed.send_event(Event::ToggleHostEnable(0, 0));
+		hed.handle_events();
+
+		assert_eq!(hed.hosts_info_draft.content, "#(hed) 10.0.0.2 a.test\n");
+		assert!(hed.channel.recv().is_none());
+	}
+
+	#[test]
+	fn test_pending_edit_before_event() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.hosts_info_draft.content.push_str("127.0.0.1 b.test\n");
+		hed.edit_content();
+		hed.send_event(Event::ToggleHostEnable(0, 0));
+		hed.handle_events();
+
+		assert_eq!(
+			hed.hosts_info_draft.content,
+			"127.0.0.1 b.test\n#(hed) 127.0.0.1 a.test\n"
+		);
+	}
+
+	#[test]
+	fn test_handle_worker_events() {
+		let mut hed = Hed {
+			sys_hosts_loading: true,
+			..Default::default()
+		};
+		let tx = hed.channel.tx.clone();
+		thread::spawn(move || {
+			let mut hosts_info = HostsInfo::default();
+			hosts_info.content = "127.0.0.1 a.test\n".to_string();
+			hosts_info.update_list();
+			tx.send(Event::ParseHostsOk(PathBuf::new(), hosts_info));
+		})
+		.join()
+		.unwrap();
+		hed.handle_events();
+
+		assert!(!hed.sys_hosts_loading);
+		assert_eq!(hed.hosts_info.list.len(), 1);
+		assert!(!hed.is_hosts_changed());
+	}
+
+	#[test]
+	fn test_failed_save_keeps_draft() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.loaded = true;
+		let err = anyhow!(io::Error::from(io::ErrorKind::PermissionDenied));
+		let error = HostsError::new(FileOp::Save, Path::new("hosts"), &err);
+		hed.send_event(Event::HostsErr(PathBuf::new(), error));
+		hed.handle_events();
+
+		assert_eq!(hed.hosts_info_draft.content, "127.0.0.1 a.test\n");
+		assert!(hed.error.is_some());
+		assert!(hed.can_save());
+	}
+
+	#[test]
+	fn test_confirm_close() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.loaded = true;
+		assert!(hed.request_close());
+
+		hed.send_event(Event::ToggleHostEnable(0, 0));
+		hed.handle_events();
+		assert!(!hed.request_close());
+		assert_eq!(hed.pending_discard, Some(DiscardAction::Close));
+
+		hed.cancel_discard();
+		assert!(hed.opened_window.is_none());
+		assert!(!hed.take_close_requested());
+
+		assert!(!hed.request_close());
+		hed.confirm_discard_draft();
+		assert!(hed.take_close_requested());
+		assert!(hed.request_close());
+	}
+
+	#[test]
+	fn test_close_waits_for_loading() {
+		let err = anyhow!(io::Error::from(io::ErrorKind::PermissionDenied));
+		let error = || HostsError::new(FileOp::Save, Path::new("hosts"), &err);
+
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.loaded = true;
+		hed.sys_hosts_loading = true;
+		assert!(!hed.request_close());
+		assert!(hed.opened_window.is_none());
+		hed.send_event(Event::HostsErr(PathBuf::new(), error()));
+		hed.handle_events();
+		assert!(hed.take_close_requested());
+
+		hed.send_event(Event::ToggleHostEnable(0, 0));
+		hed.handle_events();
+		hed.sys_hosts_loading = true;
+		assert!(!hed.request_close());
+		hed.send_event(Event::HostsErr(PathBuf::new(), error()));
+		hed.handle_events();
+		assert!(!hed.take_close_requested());
+		assert_eq!(hed.pending_discard, Some(DiscardAction::Close));
+	}
+
+	#[test]
+	fn test_undo_only_last_save() {
+		let mut hed = hed_with_content("127.0.0.1 b.test\n");
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.loaded = true;
+		let change = |before: &str, after: &str| {
+			let mut before_info = HostsInfo::default();
+			before_info.content = before.to_string();
+			SavedChange {
+				path: PathBuf::new(),
+				before: before_info,
+				after: after.to_string(),
+			}
+		};
+
+		let first = change("", "127.0.0.1 a.test\n");
+		let last = change("127.0.0.1 a.test\n", "127.0.0.1 b.test\n");
+		assert!(!hed.can_undo_save(&first));
+		assert!(hed.can_undo_save(&last));
+	}
+
+	#[test]
+	fn test_confirm_hosts_path_change() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.loaded = true;
+		hed.send_event(Event::ToggleHostEnable(0, 0));
+		hed.handle_events();
+		hed.open_settings_window();
+		hed.settings_draft.hosts_path = "other".to_string();
+		hed.apply_settings();
+
+		assert_eq!(hed.pending_discard, Some(DiscardAction::ChangeHostsPath));
+		assert!(hed.settings.hosts_path.is_empty());
+		assert!(hed.is_hosts_changed());
+
+		hed.cancel_discard();
+		assert!(hed.is_window_open(OpenedWindow::Settings));
+		hed.apply_settings();
+		hed.confirm_discard_draft();
+		assert_eq!(hed.settings.hosts_path, "other");
+		assert_eq!(hed.hosts_path, PathBuf::from("other"));
+	}
+
+	#[test]
+	fn test_confirm_reload() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.loaded = true;
+		hed.send_event(Event::ToggleHostEnable(0, 0));
+		hed.handle_events();
+		hed.reload_hosts();
+
+		assert_eq!(hed.pending_discard, Some(DiscardAction::Reload));
+		assert!(!hed.sys_hosts_loading);
+		assert!(hed.is_hosts_changed());
+	}
+
+	#[test]
+	fn test_revert_changes() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.send_event(Event::RenameHost(0, 0, "b.test".to_string()));
+		hed.handle_events();
+		assert_eq!(hed.hosts_info_draft.content, "127.0.0.1 b.test\n");
+
+		hed.send_event(Event::DeleteHost(0, 0));
+		hed.send_event(Event::RestoreHosts(
+			"127.0.0.1".to_string(),
+			vec![RemovedHost {
+				name: "a.test".to_string(),
+				enabled: true,
+			}],
+		));
+		hed.handle_events();
+		assert!(!hed.is_hosts_changed());
+	}
+
+	#[test]
+	fn test_confirm_protected() {
+		let mut hed = hed_with_content("127.0.0.1 localhost\n");
+		hed.send_event(Event::ToggleHostEnable(0, 0));
+		hed.handle_events();
+		assert_eq!(hed.pending_protected_names(), ["localhost"]);
+		assert_eq!(hed.hosts_info_draft.content, "127.0.0.1 localhost\n");
+
+		hed.cancel_protected();
+		assert!(hed.opened_window.is_none());
+		assert!(hed.pending_protected_names().is_empty());
+
+		hed.send_event(Event::DeleteItem(0));
+		hed.handle_events();
+		hed.confirm_protected();
+		assert!(hed.hosts_info_draft.list.is_empty());
+	}
+
+	#[rstest]
+	#[case(Event::EditItemIp(0, "10.0.0.2".to_string()), true)]
+	#[case(Event::EditItemIp(1, "10.0.0.2".to_string()), false)]
+	#[case(Event::EditItemIp(0, "127.0.0.1x".to_string()), false)]
+	#[case(Event::MoveHost((0, 0), 1, 0), true)]
+	#[case(Event::MoveHost((1, 0), 0, 0), false)]
+	#[case(Event::OpenTimerWindow(TimerTarget::Host((0, 0))), true)]
+	#[case(Event::OpenTimerWindow(TimerTarget::Item(1)), false)]
+	fn test_protected_events(#[case] event: Event, #[case] protected: bool) {
+		let mut hed =
+			hed_with_content("127.0.0.1 localhost\n10.0.0.1 a.test\n");
+		hed.hosts_info_draft.list[1].id = 1;
+		hed.send_event(event);
+		hed.handle_events();
+
+		let names: &[&str] = if protected {
+			&["localhost"]
+		} else {
+			&[]
+		};
+		assert_eq!(hed.pending_protected_names(), names);
+	}
+
+	#[test]
+	fn test_protected_find_replace() {
+		let mut hed = hed_with_content("127.0.0.1 localhost\n");
+		hed.find_replace.target = ReplaceTarget::Host;
+		hed.find_replace.find = "localhost".to_string();
+		hed.find_replace.replace = "local.test".to_string();
+		hed.apply_find_replace();
+		hed.handle_events();
+		assert_eq!(hed.pending_protected_names(), ["localhost"]);
+		assert_eq!(hed.hosts_info_draft.content, "127.0.0.1 localhost\n");
+
+		hed.confirm_protected();
+		assert_eq!(hed.hosts_info_draft.content, "127.0.0.1 local.test\n");
+	}
+
+	#[test]
+	fn test_protected_events_queue() {
+		let mut hed = hed_with_content("127.0.0.1 localhost\n");
+		hed.send_event(Event::ToggleHostEnable(0, 0));
+		hed.send_event(Event::DeleteItem(0));
+		hed.handle_events();
+
+		hed.cancel_protected();
+		assert!(hed.is_window_open(OpenedWindow::ConfirmProtected));
+		assert_eq!(hed.pending_protected_names(), ["localhost"]);
+		hed.confirm_protected();
+		assert!(hed.opened_window.is_none());
+		assert!(hed.hosts_info_draft.list.is_empty());
+	}
+
+	#[test]
+	fn test_font_load_failed() {
+		let mut hed = Hed::default();
+		hed.settings.font_path = "missing.ttf".to_string();
+		let fonts = |path: &str| LoadedFonts {
+			custom_path: Some(path.into()),
+			custom: Some(Err("not found".to_string())),
+			fallbacks: vec![],
+		};
+		hed.send_event(Event::FontsLoaded(fonts("missing.ttf")));
+		hed.handle_events();
+
+		assert_eq!(hed.toasts.active.len(), 1);
+		assert_eq!(hed.toasts.active[0].level, ToastLevel::Error);
+		assert!(hed.take_loaded_fonts().is_some());
+
+		// fonts read for a font that was changed since are dropped
+		hed.settings.font_path.clear();
+		hed.send_event(Event::FontsLoaded(fonts("missing.ttf")));
+		hed.handle_events();
+		assert!(hed.take_loaded_fonts().is_none());
+	}
+
+	#[test]
+	fn test_edit_refreshes_lints() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		let invalid = |hed: &Hed| {
+			hed.lints
+				.iter()
+				.any(|lint| lint.rule == LintRule::InvalidLine)
+		};
+		hed.refresh_lints();
+		assert!(!invalid(&hed));
+
+		hed.hosts_info_draft.content.push_str("a.test\n");
+		hed.hosts_info_draft.update_list_by_edit();
+		hed.refresh_lints();
+		assert!(invalid(&hed));
+	}
+
+	#[test]
+	fn test_findings_toast_when_changed() {
+		let mut hed = Hed::default();
+		let mut parse = |content: &str| {
+			let mut hosts_info = HostsInfo::default();
+			hosts_info.content = content.to_string();
+			hosts_info.update_list();
+			hed.send_event(Event::ParseHostsOk(PathBuf::new(), hosts_info));
+			hed.handle_events();
+			hed.toasts.active.len()
+		};
+
+		assert_eq!(parse("203.0.113.7 paypal.com\n"), 1);
+		assert_eq!(parse("203.0.113.7 paypal.com\n10.0.0.1 a.test\n"), 1);
+		assert_eq!(parse("203.0.113.7 paypal.com stripe.com\n"), 2);
+	}
+
+	#[test]
+	fn test_restore_draft() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.loaded = true;
+		hed.restore = Some(Autosave::new(
+			PathBuf::from("hosts"),
+			"127.0.0.1 a.test b.test\n".to_string(),
+		));
+		hed.restore_draft();
+
+		assert!(hed.restore.is_none());
+		assert!(hed.is_hosts_changed());
+		assert_eq!(hed.hosts_info_draft.list[0].hosts.len(), 2);
+	}
+
+	fn loaded_tab(path: &str, content: &str) -> FileTab {
+		let mut tab = FileTab::new(PathBuf::from(path));
+		tab.hosts_info_draft.content = content.to_string();
+		tab.hosts_info_draft.update_list();
+		tab.hosts_info.clone_from(&tab.hosts_info_draft);
+		tab.loaded = true;
+		tab
+	}
+
+	#[test]
+	fn test_switch_tabs() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.loaded = true;
+		hed.is_system = true;
+		hed.tabs =
+			vec![FileTab::default(), loaded_tab("b", "10.0.0.1 b.test\n")];
+
+		hed.switch_tab(1);
+		assert_eq!(hed.hosts_path, PathBuf::from("b"));
+		assert!(!hed.is_system);
+		hed.send_event(Event::ToggleHostEnable(0, 0));
+		hed.handle_events();
+
+		hed.switch_tab(0);
+		assert!(hed.is_system);
+		assert_eq!(hed.hosts_info_draft.content, "127.0.0.1 a.test\n");
+		assert!(hed.tab_info(1).2);
+
+		assert!(!hed.request_close());
+		assert_eq!(hed.active_tab, 1);
+		hed.cancel_discard();
+
+		hed.close_tab(1);
+		assert_eq!(hed.pending_discard, Some(DiscardAction::CloseTab));
+		hed.confirm_discard_draft();
+		assert_eq!(hed.active_tab, 0);
+		assert_eq!(hed.tab_count(), 1);
+		assert!(hed.request_close());
+	}
+
+	#[test]
+	fn test_hosts_options_for_every_tab() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.loaded = true;
+		hed.tabs =
+			vec![FileTab::default(), loaded_tab("b", "# 10.0.0.1 b.test\n")];
+
+		let mut options = hed.settings.hosts_options.clone();
+		options.format.separator = Separator::Tab;
+		hed.set_hosts_options(options.clone());
+		assert!(!hed.is_hosts_changed());
+		assert_eq!(hed.hosts_info_draft.content, "127.0.0.1 a.test\n");
+
+		options.parse_commented = !options.parse_commented;
+		hed.set_hosts_options(options.clone());
+		hed.switch_tab(1);
+		assert_eq!(hed.hosts_info_draft.options, options);
+		assert_eq!(hed.hosts_info_draft.list.len(), 1);
+		assert!(!hed.is_hosts_changed());
+	}
+
+	#[test]
+	fn test_worker_event_goes_to_its_tab() {
+		let mut hed = hed_with_content("127.0.0.1 a.test\n");
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.hosts_path = PathBuf::from("a");
+		hed.loaded = true;
+		let mut tab = FileTab::new(PathBuf::from("b"));
+		tab.loading = true;
+		hed.tabs = vec![FileTab::default(), tab];
+
+		let mut hosts_info = HostsInfo::default();
+		hosts_info.content = "10.0.0.1 b.test\n".to_string();
+		hosts_info.update_list();
+		// the tab of c was closed
+		let closed =
+			Event::ParseHostsOk(PathBuf::from("c"), hosts_info.clone());
+		hed.send_event(closed);
+		hed.send_event(Event::ParseHostsOk(PathBuf::from("b"), hosts_info));
+		hed.handle_events();
+
+		assert_eq!(hed.active_tab, 0);
+		assert_eq!(hed.hosts_info_draft.content, "127.0.0.1 a.test\n");
+		assert!(!hed.tabs[1].loading);
+		assert!(hed.tabs[1].loaded);
+		assert_eq!(hed.tabs[1].hosts_info.content, "10.0.0.1 b.test\n");
+	}
+
+	#[test]
+	fn test_timers_expire_in_every_tab() {
+		let content = "127.0.0.1 a.test\n\
+		               #(hed:timer) 127.0.0.1 a.test 100 disable\n";
+		let mut hed = hed_with_content("127.0.0.1 c.test\n");
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.hosts_path = PathBuf::from("c");
+		hed.loaded = true;
+		let mut read_only = loaded_tab("a", content);
+		read_only.read_only = true;
+		// fails to save, the file doesn't exist
+		let missing = loaded_tab("/nonexistent/hosts", content);
+		hed.tabs = vec![FileTab::default(), read_only, missing];
+
+		assert!(hed.tick_timers().is_some());
+		assert_eq!(hed.hosts_path, PathBuf::from("c"));
+		let warnings = |hed: &Hed| {
+			let toasts = hed.toasts.active.iter();
+			toasts
+				.filter(|toast| toast.level == ToastLevel::Warning)
+				.count()
+		};
+		assert_eq!(warnings(&hed), 1);
+		assert!(hed.tabs[1].is_dirty());
+		assert_eq!(hed.tabs[1].hosts_info_draft.timers().count(), 0);
+		assert!(hed.tabs[2].loading);
+
+		while hed.any_loading() {
+			hed.handle_events();
+		}
+		// the failed save keeps the file as it is on disk
+		assert!(hed.tabs[2].error.is_some());
+		assert_eq!(hed.tabs[2].hosts_info.timers().count(), 1);
+		assert!(hed.tabs[2].is_dirty());
+
+		// the unwritable timers are only reported once
+		hed.tick_timers();
+		assert_eq!(warnings(&hed), 1);
+	}
+
+	#[test]
+	fn test_expired_timers_saved() {
+		let content = "127.0.0.1 a.test\n\
+		               #(hed:timer) 127.0.0.1 a.test 100 disable\n";
+		let mut hed = hed_with_content(content);
+		hed.hosts_info.clone_from(&hed.hosts_info_draft);
+		hed.loaded = true;
+		hed.sys_hosts_loading = true;
+		let mut saved = hed.hosts_info.clone();
+		saved.expire_timers(100);
+		hed.send_event(Event::ExpiredTimersSaved(PathBuf::new(), saved));
+		hed.handle_events();
+
+		assert!(!hed.sys_hosts_loading);
+		assert_eq!(hed.hosts_info.timers().count(), 0);
+		assert_eq!(hed.hosts_info.content, "#(hed) 127.0.0.1 a.test\n");
+	}
+
+	#[test]
+	fn test_normalize_preview() {
+		let content = "127.0.0.1 A.test\n\n\n127.0.0.1 b.test\n";
+		let mut hed = hed_with_content(content);
+		hed.loaded = true;
+		hed.run_command(Command::Normalize);
+		assert!(hed.is_window_open(OpenedWindow::Normalize));
+		assert!(!hed.diff.is_empty());
+		assert_eq!(hed.hosts_info_draft.content, content);
+
+		hed.apply_normalize();
+		assert_eq!(hed.hosts_info_draft.content, "127.0.0.1 a.test b.test\n");
+		assert_eq!(hed.hosts_info_draft.list[0].hosts.len(), 2);
+		assert!(hed.opened_window.is_none());
+		assert!(hed.diff.is_empty());
+	}
+
+	#[test]
+	fn test_selection_drops_removed_hosts() {
+		let mut hed = hed_with_content("10.0.0.1 a.test\n");
+		hed.send_event(Event::SelectHost((0, 0), SelectMode::Toggle));
+		hed.send_event(Event::ToggleHostsEnable(vec![(0, 0)], false));
+		hed.handle_events();
+		assert_eq!(hed.selection.len(), 1);
+
+		hed.send_event(Event::DeleteHost(0, 0));
+		hed.handle_events();
+		assert!(hed.selection.is_empty());
+	}
 }
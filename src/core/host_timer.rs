@@ -0,0 +1,103 @@
+use super::selection::HostKey;
+use crate::{
+	i18n::{t, tf},
+	util::{now_secs, parse_duration, parse_local_time, StringExt},
+};
+
+pub const HED_TIMER_MARK: &str = "#(hed:timer)";
+
+/// A host that goes back to `revert_to` at `expires_at`, in unix seconds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostTimer {
+	pub ip: String,
+	pub host: String,
+	pub expires_at: u64,
+	pub revert_to: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerTarget {
+	Item(usize),
+	Host(HostKey),
+}
+
+#[derive(Default)]
+pub struct TimerForm {
+	pub target: Option<TimerTarget>,
+	pub enable: bool,
+	pub until: String,
+	pub until_error: String,
+}
+
+impl HostTimer {
+	/// Parse `#(hed:timer) <ip> <host> <expires_at> <enable|disable>`.
+	pub fn parse(s: &str) -> Option<Self> {
+		let mut parts = s.strip_prefix(HED_TIMER_MARK)?.split_whitespace();
+		let timer = Self {
+			ip: parts.next()?.to_string(),
+			host: parts.next()?.to_string(),
+			expires_at: parts.next()?.parse().ok()?,
+			revert_to: match parts.next()? {
+				"enable" => true,
+				"disable" => false,
+				_ => return None,
+			},
+		};
+
+		parts.next().is_none().then_some(timer)
+	}
+
+	pub fn to_line(&self) -> String {
+		format!(
+			"{} {} {} {} {}",
+			HED_TIMER_MARK,
+			self.ip,
+			self.host,
+			self.expires_at,
+			if self.revert_to {
+				"enable"
+			} else {
+				"disable"
+			}
+		)
+	}
+
+	pub fn is_for(&self, ip: &str, host: &str) -> bool {
+		self.ip == ip && self.host == host
+	}
+
+	pub fn remaining(&self, now: u64) -> u64 {
+		self.expires_at.saturating_sub(now)
+	}
+}
+
+impl TimerForm {
+	pub fn reset(&mut self) {
+		self.target = None;
+		self.enable = false;
+		self.until.clear();
+		self.until_error.clear();
+	}
+
+	/// The expiry from a duration such as `30m` or a local time such as
+	/// `18:30`.
+	pub fn expires_at(&mut self) -> Option<u64> {
+		self.until.make_trim();
+		let now = now_secs();
+
+		if let Some(duration) = parse_duration(&self.until) {
+			return Some(now + duration);
+		}
+		match parse_local_time(&self.until, now) {
+			Some(ts) if ts > now => Some(ts),
+			Some(_) => {
+				self.until_error = t("error.until_past").to_string();
+				None
+			}
+			None => {
+				self.until_error = tf("error.until_invalid", &[&self.until]);
+				None
+			}
+		}
+	}
+}
@@ -0,0 +1,189 @@
+use std::{
+	io,
+	path::{Path, PathBuf},
+};
+
+use super::file_format::EncodingError;
+use crate::i18n::{t, tf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOp {
+	Read,
+	Save,
+	SaveAs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+	PermissionDenied,
+	NotFound,
+	InvalidEncoding,
+	Io,
+}
+
+/// What the user can do about a [`HostsError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recovery {
+	Retry,
+	RequestElevation,
+	OpenReadOnly,
+	ChooseFile,
+	SaveDraftAs,
+}
+
+/// A failure to read or write a hosts file.
+#[derive(Debug, Clone)]
+pub struct HostsError {
+	pub op: FileOp,
+	pub kind: ErrorKind,
+	pub path: PathBuf,
+	detail: String,
+}
+
+impl HostsError {
+	pub fn new(op: FileOp, path: &Path, err: &anyhow::Error) -> Self {
+		let kind = if err.downcast_ref::<EncodingError>().is_some() {
+			ErrorKind::InvalidEncoding
+		} else {
+			match err.downcast_ref::<io::Error>().map(io::Error::kind) {
+				Some(io::ErrorKind::PermissionDenied) => {
+					ErrorKind::PermissionDenied
+				}
+				Some(io::ErrorKind::NotFound) => ErrorKind::NotFound,
+				_ => ErrorKind::Io,
+			}
+		};
+		let detail = match kind {
+			ErrorKind::PermissionDenied => t("error.permission_denied"),
+			ErrorKind::NotFound => t("error.not_found"),
+			ErrorKind::InvalidEncoding | ErrorKind::Io => "",
+		};
+		let detail = if detail.is_empty() {
+			err.to_string()
+		} else {
+			detail.to_string()
+		};
+
+		Self {
+			op,
+			kind,
+			path: path.to_path_buf(),
+			detail,
+		}
+	}
+
+	pub fn no_sys_hosts_path() -> Self {
+		Self {
+			op: FileOp::Read,
+			kind: ErrorKind::NotFound,
+			path: PathBuf::new(),
+			detail: String::new(),
+		}
+	}
+
+	pub fn title(&self) -> String {
+		if self.path.as_os_str().is_empty() {
+			return t("error.sys_hosts_path").to_string();
+		}
+		let path = self.path.display();
+		match self.op {
+			FileOp::Read => tf("error.read_failed", &[&path]),
+			FileOp::Save | FileOp::SaveAs => tf("error.save_failed", &[&path]),
+		}
+	}
+
+	pub fn detail(&self) -> &str {
+		&self.detail
+	}
+
+	/// The ways out of this error, the most likely to help first.
+	pub fn recoveries(&self) -> Vec<Recovery> {
+		use ErrorKind::*;
+		use Recovery::*;
+
+		if self.path.as_os_str().is_empty() {
+			return vec![ChooseFile];
+		}
+
+		match (self.op, self.kind) {
+			(FileOp::Read, PermissionDenied) => {
+				vec![RequestElevation, Retry, ChooseFile]
+			}
+			(FileOp::Read, NotFound | InvalidEncoding) => {
+				vec![ChooseFile, Retry]
+			}
+			(FileOp::Read, Io) => vec![Retry, ChooseFile],
+			(FileOp::Save, PermissionDenied) => {
+				vec![RequestElevation, Retry, OpenReadOnly, SaveDraftAs]
+			}
+			// the draft has to be edited before it can be saved at all
+			(FileOp::Save | FileOp::SaveAs, InvalidEncoding) => vec![],
+			(FileOp::Save, NotFound | Io) => vec![Retry, SaveDraftAs],
+			(FileOp::SaveAs, _) => vec![SaveDraftAs],
+		}
+	}
+}
+
+impl Recovery {
+	pub fn label(&self) -> &'static str {
+		t(match self {
+			Recovery::Retry => "recovery.retry",
+			Recovery::RequestElevation => "recovery.elevate",
+			Recovery::OpenReadOnly => "recovery.read_only",
+			Recovery::ChooseFile => "recovery.choose_file",
+			Recovery::SaveDraftAs => "recovery.save_draft_as",
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{io, path::Path};
+
+	use anyhow::anyhow;
+	use rstest::rstest;
+
+	use super::{ErrorKind, FileOp, HostsError, Recovery};
+	use crate::core::file_format::EncodingError;
+
+	#[rstest]
+	#[case::denied(
+		anyhow!(io::Error::from(io::ErrorKind::PermissionDenied)),
+		ErrorKind::PermissionDenied
+	)]
+	#[case::not_found(
+		anyhow!(io::Error::from(io::ErrorKind::NotFound)),
+		ErrorKind::NotFound
+	)]
+	#[case::encoding(anyhow!(EncodingError::Unknown), ErrorKind::InvalidEncoding)]
+	#[case::other(
+		anyhow!(io::Error::from(io::ErrorKind::Interrupted)),
+		ErrorKind::Io
+	)]
+	fn test_error_kind(#[case] err: anyhow::Error, #[case] kind: ErrorKind) {
+		let error = HostsError::new(FileOp::Read, Path::new("hosts"), &err);
+
+		assert_eq!(error.kind, kind);
+		assert!(!error.detail().is_empty());
+	}
+
+	#[test]
+	fn test_recoveries() {
+		let denied = anyhow!(io::Error::from(io::ErrorKind::PermissionDenied));
+		let save = HostsError::new(FileOp::Save, Path::new("hosts"), &denied);
+
+		assert_eq!(
+			save.recoveries(),
+			vec![
+				Recovery::RequestElevation,
+				Recovery::Retry,
+				Recovery::OpenReadOnly,
+				Recovery::SaveDraftAs
+			]
+		);
+		assert_eq!(
+			HostsError::no_sys_hosts_path().recoveries(),
+			vec![Recovery::ChooseFile]
+		);
+	}
+}
@@ -1,27 +1,53 @@
 use std::{
 	collections::{HashMap, HashSet},
 	fs,
-	path::PathBuf,
+	path::{Path, PathBuf},
+	sync::atomic::{AtomicUsize, Ordering::Relaxed},
 };
 
 use anyhow::Result;
 use indexmap::IndexMap;
 
-use super::{item::Item, item_form::ItemForm};
-use crate::util::{is_ip, StringExt};
+use super::{
+	changes::RemovedHost,
+	file_format::FileFormat,
+	find_replace::{ReplaceLocation, Replacement},
+	host_timer::{HostTimer, HED_TIMER_MARK},
+	hosts_options::{HostsOptions, Separator},
+	item::{Host, Item},
+	item_form::ItemForm,
+	normalize::{normalize_lines, NormalizeSteps},
+	selection::HostKey,
+};
+use crate::util::{copy_elevated, is_ip, StringExt, TempFile, GLOBAL_ID};
 
 const HED_COMMENT_MARK: &str = "#(hed)";
 
+/// Revisions are unique across all hosts infos, so that a cache of one
+/// draft is never taken for another. Unlike ids they count up in tests too.
+static REVISION: AtomicUsize = AtomicUsize::new(1);
+
+fn next_revision() -> usize {
+	REVISION.fetch_add(1, Relaxed)
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct HostsInfo {
 	pub content: String,
 	pub list: Vec<Item>,
+	pub options: HostsOptions,
+	pub file_format: FileFormat,
 	lines: Vec<Line>,
+	/// The content `lines` was parsed from, line by line, until the list is
+	/// edited.
+	parsed_content: Option<String>,
+	revision: usize,
 }
 
 #[derive(Debug, Clone)]
 pub enum Line {
 	Valid(ValidLine),
+	Timer(HostTimer),
 	Comment(String),
 	Empty,
 	Other(String),
@@ -29,34 +55,127 @@ pub enum Line {
 
 #[derive(Debug, Clone)]
 pub struct ValidLine {
-	ip: String,
-	hosts: Vec<String>,
-	comment: Option<String>,
-	enabled: bool,
+	pub(super) ip: String,
+	pub(super) hosts: Vec<String>,
+	pub(super) comment: Option<String>,
+	pub(super) enabled: bool,
 }
 
 impl HostsInfo {
-	pub fn parse_from_file(hosts_path: PathBuf) -> Result<Self> {
-		let content = fs::read_to_string(hosts_path)?;
-		let lines = content_to_lines(&content);
+	pub fn parse_from_file(
+		hosts_path: PathBuf,
+		options: HostsOptions,
+	) -> Result<Self> {
+		let bytes = fs::read(hosts_path)?;
+		let (content, file_format) = FileFormat::decode(&bytes)?;
+		let lines = content_to_lines(&content, &options);
 		let list = lines_to_list(&lines);
 
 		Ok(Self {
+			parsed_content: Some(content.clone()),
 			content,
 			list,
+			options,
+			file_format,
 			lines,
+			revision: next_revision(),
 		})
 	}
 
+	/// Changes whenever the list or the content is updated.
+	pub fn revision(&self) -> usize {
+		self.revision
+	}
+
 	pub fn update_content(&mut self) {
 		self.lines = new_lines_by_list(&self.lines, &self.list);
 		self.content =
-			lines_to_content(&self.lines, cfg!(target_os = "windows"));
+			lines_to_content(&self.lines, &self.file_format, &self.options);
+		self.parsed_content = None;
+		self.revision = next_revision();
+	}
+
+	/// Like [`Self::update_content`], but also moves lines so that their order
+	/// follows the order of `list`.
+	pub fn update_content_by_order(&mut self) {
+		self.lines = new_lines_by_list(&self.lines, &self.list);
+		self.lines = reorder_lines(&self.lines, &self.list);
+		self.content =
+			lines_to_content(&self.lines, &self.file_format, &self.options);
+		self.parsed_content = None;
+		self.revision = next_revision();
+	}
+
+	/// The content with the chosen `steps` of the normalize command applied,
+	/// the draft stays as it is so that the result can be previewed.
+	pub fn normalized(&self, steps: &NormalizeSteps) -> String {
+		let lines = content_to_lines(&self.content, &self.options);
+		let lines = normalize_lines(lines, steps);
+		lines_to_content(&lines, &self.file_format, &self.options)
 	}
 
 	pub fn update_list(&mut self) {
-		self.lines = content_to_lines(&self.content);
+		self.lines = content_to_lines(&self.content, &self.options);
 		self.list = lines_to_list(&self.lines);
+		self.parsed_content = Some(self.content.clone());
+		self.revision = next_revision();
+	}
+
+	/// Like [`Self::update_list`], but only reparses the lines that changed
+	/// since the last parse. Items whose ip is on none of them are kept as
+	/// they are.
+	pub fn update_list_by_edit(&mut self) {
+		let Some(parsed) = self.parsed_content.take() else {
+			self.update_list();
+			return;
+		};
+
+		let old_texts: Vec<&str> = parsed.lines().collect();
+		let new_texts: Vec<&str> = self.content.lines().collect();
+		if old_texts.len() != self.lines.len() {
+			self.update_list();
+			return;
+		}
+
+		let prefix = old_texts
+			.iter()
+			.zip(&new_texts)
+			.take_while(|(old, new)| old == new)
+			.count();
+		let max_suffix = old_texts.len().min(new_texts.len()) - prefix;
+		let suffix = old_texts
+			.iter()
+			.rev()
+			.zip(new_texts.iter().rev())
+			.take(max_suffix)
+			.take_while(|(old, new)| old == new)
+			.count();
+
+		let new_lines: Vec<Line> = new_texts[prefix..new_texts.len() - suffix]
+			.iter()
+			.map(|text| parse_line(text, &self.options))
+			.collect();
+		let old_lines: Vec<Line> = self
+			.lines
+			.splice(prefix..old_texts.len() - suffix, new_lines)
+			.collect();
+
+		let changed_ips: HashSet<&str> = old_lines
+			.iter()
+			.chain(&self.lines[prefix..self.lines.len() - suffix])
+			.filter_map(|line| match line {
+				Line::Valid(valid_line) => Some(valid_line.ip.as_str()),
+				_ => None,
+			})
+			.collect();
+
+		if !changed_ips.is_empty() {
+			let list = std::mem::take(&mut self.list);
+			self.list = update_changed_items(list, &self.lines, &changed_ips);
+		}
+
+		self.parsed_content = Some(self.content.clone());
+		self.revision = next_revision();
 	}
 
 	pub fn add_item(&mut self, form: &ItemForm) {
@@ -72,6 +191,26 @@ impl HostsInfo {
 		}
 	}
 
+	/// Put back hosts removed from the item with `ip`, creating it if needed.
+	pub fn restore_hosts(&mut self, ip: &str, hosts: Vec<RemovedHost>) {
+		let hosts = hosts
+			.into_iter()
+			.map(|host| Host {
+				id: GLOBAL_ID.next(),
+				name: host.name,
+				enabled: host.enabled,
+			})
+			.collect();
+
+		if let Some(item) = self.list.iter_mut().find(|item| item.ip == ip) {
+			item.push_hosts(hosts);
+		} else {
+			let mut item = Item::new(ip, vec![], true);
+			item.push_hosts(hosts);
+			self.list.push(item);
+		}
+	}
+
 	pub fn get_item_mut(&mut self, item_id: usize) -> Option<&mut Item> {
 		self.list.iter_mut().find(|item| item.id == item_id)
 	}
@@ -83,6 +222,216 @@ impl HostsInfo {
 		}
 	}
 
+	/// Move an item so that it is placed before the item currently at `to`.
+	pub fn move_item(&mut self, item_id: usize, to: usize) {
+		let Some(from) = self.list.iter().position(|item| item.id == item_id)
+		else {
+			return;
+		};
+		let item = self.list.remove(from);
+		let to = if to > from {
+			to - 1
+		} else {
+			to
+		};
+		self.list.insert(to.min(self.list.len()), item);
+	}
+
+	/// Move a host into `to_item_id`, placing it before the host currently
+	/// at `to`.
+	pub fn move_host(&mut self, key: HostKey, to_item_id: usize, to: usize) {
+		let (item_id, host_id) = key;
+		let Some((from, host)) = self
+			.get_item_mut(item_id)
+			.and_then(|item| item.take_host(host_id))
+		else {
+			return;
+		};
+		let to = if item_id == to_item_id && to > from {
+			to - 1
+		} else {
+			to
+		};
+		if let Some(item) = self.get_item_mut(to_item_id) {
+			item.insert_host(to, host);
+		} else if let Some(item) = self.get_item_mut(item_id) {
+			item.insert_host(from, host);
+		}
+		self.list.retain(|item| !item.hosts.is_empty());
+	}
+
+	pub fn host_keys(&self) -> Vec<HostKey> {
+		self.list
+			.iter()
+			.flat_map(|item| item.hosts.iter().map(|host| (item.id, host.id)))
+			.collect()
+	}
+
+	pub fn remove_hosts(&mut self, keys: &[HostKey]) {
+		for (item_id, host_id) in keys {
+			if let Some(item) = self.get_item_mut(*item_id) {
+				item.remove_host(*host_id);
+			}
+		}
+		self.list.retain(|item| !item.hosts.is_empty());
+	}
+
+	/// Copy hosts to the item with `ip`, creating it if needed. With `remove`
+	/// the hosts are moved instead.
+	pub fn transfer_hosts(&mut self, keys: &[HostKey], ip: &str, remove: bool) {
+		let mut hosts: Vec<Host> = vec![];
+
+		for (item_id, host_id) in keys {
+			let Some(item) = self.list.iter().find(|item| item.id == *item_id)
+			else {
+				continue;
+			};
+			if remove && item.ip == ip {
+				continue;
+			}
+			if let Some(host) = item.get_host(*host_id) {
+				hosts.push(Host {
+					id: if remove {
+						host.id
+					} else {
+						GLOBAL_ID.next()
+					},
+					..host.clone()
+				});
+			}
+		}
+
+		if remove {
+			let moved: Vec<HostKey> = keys
+				.iter()
+				.filter(|(item_id, _)| {
+					self.list
+						.iter()
+						.any(|item| item.id == *item_id && item.ip != ip)
+				})
+				.copied()
+				.collect();
+			self.remove_hosts(&moved);
+		}
+
+		if let Some(item) = self.list.iter_mut().find(|item| item.ip == ip) {
+			item.push_hosts(hosts);
+		} else if !hosts.is_empty() {
+			let mut item = Item::new(ip, vec![], true);
+			item.push_hosts(hosts);
+			self.list.push(item);
+		}
+	}
+
+	pub fn apply_replacements(&mut self, replacements: &[Replacement]) {
+		let mut line_map: HashMap<usize, &str> = HashMap::new();
+
+		for replacement in replacements.iter().filter(|r| r.valid) {
+			match replacement.location {
+				ReplaceLocation::Ip(item_id) => {
+					if let Some(item) = self.get_item_mut(item_id) {
+						item.ip.clone_from(&replacement.new);
+					}
+				}
+				ReplaceLocation::Host(item_id, host_id) => {
+					if let Some(item) = self.get_item_mut(item_id) {
+						item.rename_host(host_id, replacement.new.clone());
+					}
+				}
+				ReplaceLocation::Line(idx) => {
+					line_map.insert(idx, &replacement.new);
+				}
+			}
+		}
+
+		if line_map.is_empty() {
+			self.merge_items();
+			self.update_content();
+			return;
+		}
+
+		let mut content = String::new();
+		for (i, line) in self.content.split_inclusive('\n').enumerate() {
+			let text = line.trim_end_matches(['\r', '\n']);
+			content.push_str(line_map.get(&i).copied().unwrap_or(text));
+			content.push_str(&line[text.len()..]);
+		}
+		self.content = content;
+		self.update_list_by_edit();
+	}
+
+	pub fn timers(&self) -> impl Iterator<Item = &HostTimer> {
+		self.lines.iter().filter_map(|line| match line {
+			Line::Timer(timer) => Some(timer),
+			_ => None,
+		})
+	}
+
+	/// Add or replace the timer of a host, call `update_content` after.
+	pub fn set_timer(&mut self, timer: HostTimer) {
+		self.remove_timer(&timer.ip, &timer.host);
+		let idx = if matches!(self.lines.last(), Some(Line::Empty)) {
+			self.lines.len() - 1
+		} else {
+			self.lines.len()
+		};
+		self.lines.insert(idx, Line::Timer(timer));
+	}
+
+	/// Remove the timer of a host, call `update_content` after.
+	pub fn remove_timer(&mut self, ip: &str, host: &str) {
+		self.lines.retain(
+			|line| !matches!(line, Line::Timer(timer) if timer.is_for(ip, host)),
+		);
+	}
+
+	/// Whether any timer is up at `now`.
+	pub fn has_expired_timers(&self, now: u64) -> bool {
+		self.timers().any(|timer| timer.expires_at <= now)
+	}
+
+	/// Revert the hosts whose timer is up at `now`, returns whether any was.
+	pub fn expire_timers(&mut self, now: u64) -> bool {
+		let expired: Vec<HostTimer> = self
+			.timers()
+			.filter(|timer| timer.expires_at <= now)
+			.cloned()
+			.collect();
+
+		if expired.is_empty() {
+			return false;
+		}
+
+		for timer in &expired {
+			for item in self.list.iter_mut().filter(|item| item.ip == timer.ip)
+			{
+				for host in
+					item.hosts.iter_mut().filter(|host| host.name == timer.host)
+				{
+					host.enabled = timer.revert_to;
+				}
+			}
+			self.remove_timer(&timer.ip, &timer.host);
+		}
+		self.update_content();
+
+		true
+	}
+
+	fn merge_items(&mut self) {
+		let mut item_map: IndexMap<String, Item> = IndexMap::new();
+
+		for item in self.list.drain(..) {
+			if let Some(exist) = item_map.get_mut(&item.ip) {
+				exist.merge(item);
+			} else {
+				item_map.insert(item.ip.clone(), item);
+			}
+		}
+
+		self.list = item_map.into_values().collect();
+	}
+
 	pub fn save_to_file(&self, hosts_path: PathBuf) -> Result<()> {
 		let metadata = fs::metadata(&hosts_path)?;
 		let mut permissions = metadata.permissions();
@@ -92,73 +441,209 @@ impl HostsInfo {
 			fs::set_permissions(&hosts_path, permissions)?;
 		}
 
-		let tmp_file = std::env::temp_dir().join("hed_tmp");
-		fs::write(&tmp_file, &self.content)?;
-		fs::copy(&tmp_file, hosts_path)?;
-		fs::remove_file(&tmp_file)?;
+		let mut tmp_file = TempFile::new()?;
+		tmp_file.write_all(&self.file_format.encode(&self.content)?)?;
+		fs::copy(tmp_file.path(), hosts_path)?;
+
+		Ok(())
+	}
+
+	/// Save through a copy the system runs as administrator.
+	pub fn save_to_file_elevated(&self, hosts_path: PathBuf) -> Result<()> {
+		let mut tmp_file = TempFile::new()?;
+		tmp_file.write_all(&self.file_format.encode(&self.content)?)?;
+
+		copy_elevated(tmp_file.path(), &hosts_path)
+	}
+
+	/// Read through a copy the system runs as administrator.
+	pub fn parse_from_file_elevated(
+		hosts_path: PathBuf,
+		options: HostsOptions,
+	) -> Result<Self> {
+		// created first so that the copy keeps it readable by the user
+		let tmp_file = TempFile::new()?;
+		copy_elevated(&hosts_path, tmp_file.path())?;
+
+		Self::parse_from_file(tmp_file.path().to_path_buf(), options)
+	}
+
+	/// Write the content to another file than the hosts file, creating it.
+	pub fn write_to_file(&self, path: &Path) -> Result<()> {
+		fs::write(path, self.file_format.encode(&self.content)?)?;
 
 		Ok(())
 	}
 }
 
-fn content_to_lines(s: &str) -> Vec<Line> {
-	let mut lines = vec![];
+fn content_to_lines(s: &str, options: &HostsOptions) -> Vec<Line> {
+	s.lines().map(|l| parse_line(l, options)).collect()
+}
 
-	for l in s.lines() {
-		let line = l.trim().to_string();
+pub(super) fn parse_line(l: &str, options: &HostsOptions) -> Line {
+	let line = l.trim();
 
-		if line.is_empty() {
-			lines.push(Line::Empty);
-			continue;
-		}
+	if line.is_empty() {
+		return Line::Empty;
+	}
 
-		if line.starts_with('#') && !line.starts_with(HED_COMMENT_MARK) {
-			lines.push(Line::Comment(line));
-			continue;
-		}
+	if line.starts_with(HED_TIMER_MARK) {
+		return match HostTimer::parse(line) {
+			Some(timer) => Line::Timer(timer),
+			None => Line::Comment(line.to_string()),
+		};
+	}
 
-		if let Some(valid_line) = parse_valid_line(&line) {
-			lines.push(Line::Valid(valid_line));
-		} else {
-			lines.push(Line::Other(line));
-		}
+	if line.starts_with('#') && !line.starts_with(HED_COMMENT_MARK) {
+		return match options
+			.parse_commented
+			.then(|| parse_commented_line(line))
+			.flatten()
+		{
+			Some(valid_line) => Line::Valid(valid_line),
+			None => Line::Comment(line.to_string()),
+		};
 	}
 
-	lines
+	match parse_valid_line(line) {
+		Some(valid_line) => Line::Valid(valid_line),
+		None => Line::Other(line.to_string()),
+	}
 }
 
-fn lines_to_content(lines: &[Line], is_win: bool) -> String {
-	let mut text_lines: Vec<String> = vec![];
+fn lines_to_content(
+	lines: &[Line],
+	file_format: &FileFormat,
+	options: &HostsOptions,
+) -> String {
+	let ip_width = if options.format.align_ip {
+		lines
+			.iter()
+			.filter_map(|line| match line {
+				Line::Valid(valid_line) => {
+					Some(ip_column(valid_line, options).chars().count())
+				}
+				_ => None,
+			})
+			.max()
+			.unwrap_or(0)
+	} else {
+		0
+	};
+
+	let eol = file_format.eol.as_str();
+	let mut content = String::new();
+	let mut is_first_line = true;
+	let mut push_line = |text: &str| {
+		if !is_first_line {
+			content.push_str(eol);
+		}
+		content.push_str(text);
+		is_first_line = false;
+	};
 
 	for line in lines {
-		let text = match line {
+		match line {
 			Line::Valid(valid_line) => {
-				let mut vs = vec![];
-				if !valid_line.enabled {
-					vs.push(HED_COMMENT_MARK.to_string());
-				}
-				vs.push(valid_line.ip.clone());
-				vs.append(&mut valid_line.hosts.clone());
-				if let Some(comment) = &valid_line.comment {
-					vs.push(format!("# {}", comment));
+				for text in format_valid_line(valid_line, options, ip_width) {
+					push_line(&text);
 				}
-				vs.join(" ")
 			}
-			Line::Comment(s) => s.clone(),
-			Line::Other(s) => s.clone(),
-			Line::Empty => String::new(),
-		};
+			Line::Timer(timer) => {
+				push_line(&timer.to_line());
+			}
+			Line::Comment(s) | Line::Other(s) => {
+				push_line(s);
+			}
+			Line::Empty => {
+				push_line("");
+			}
+		}
+	}
 
-		text_lines.push(text);
+	if !file_format.trailing_newline {
+		if let Some(stripped) = content.strip_suffix(eol) {
+			content.truncate(stripped.len());
+		}
 	}
 
-	let eol = if is_win {
-		"\r\n"
+	content
+}
+
+fn ip_column(valid_line: &ValidLine, options: &HostsOptions) -> String {
+	if valid_line.enabled {
+		valid_line.ip.clone()
 	} else {
-		"\n"
-	};
+		format!("{} {}", options.disabled_mark.as_str(), valid_line.ip)
+	}
+}
+
+/// Write a valid line, splitting it into several lines with the same ip when
+/// it exceeds the limits of the format options.
+pub(super) fn format_valid_line(
+	valid_line: &ValidLine,
+	options: &HostsOptions,
+	ip_width: usize,
+) -> Vec<String> {
+	let format = &options.format;
+	let sep = format.separator.as_str();
+
+	let mut ip_column = ip_column(valid_line, options);
+	if format.separator == Separator::Space {
+		let pad = ip_width.saturating_sub(ip_column.chars().count());
+		ip_column.push_str(&" ".repeat(pad));
+	}
+	let ip_len = ip_column.chars().count();
+	let comment = valid_line
+		.comment
+		.as_ref()
+		.map(|comment| format!("{}# {}", sep, comment));
+	// the comment goes on the first line, and counts toward its length
+	let comment_len = comment.as_ref().map_or(0, |c| c.chars().count());
+
+	let mut chunks: Vec<Vec<&str>> = vec![];
+	let mut len = 0;
+
+	for host in &valid_line.hosts {
+		let host_len = sep.len() + host.chars().count();
+		match chunks.last_mut() {
+			Some(chunk)
+				if chunk.len() < format.hosts_limit()
+					&& len + host_len <= format.length_limit() =>
+			{
+				chunk.push(host);
+				len += host_len;
+			}
+			_ => {
+				let first = chunks.is_empty();
+				chunks.push(vec![host]);
+				len = ip_len + host_len;
+				if first {
+					len += comment_len;
+				}
+			}
+		}
+	}
+
+	if chunks.is_empty() {
+		chunks.push(vec![]);
+	}
 
-	text_lines.join(eol)
+	chunks
+		.into_iter()
+		.enumerate()
+		.map(|(i, chunk)| {
+			let mut text = ip_column.clone();
+			for host in chunk {
+				text.push_str(sep);
+				text.push_str(host);
+			}
+			if let (0, Some(comment)) = (i, &comment) {
+				text.push_str(comment);
+			}
+			text
+		})
+		.collect()
 }
 
 fn parse_valid_line(s: &str) -> Option<ValidLine> {
@@ -177,6 +662,27 @@ fn parse_valid_line(s: &str) -> Option<ValidLine> {
 	}
 }
 
+fn parse_commented_line(s: &str) -> Option<ValidLine> {
+	let striped_s = s.strip_prefix('#')?.trim_start();
+	if striped_s.starts_with('#') {
+		return None;
+	}
+	let mut valid_line = parse_valid_line(striped_s)?;
+	valid_line.enabled = false;
+	Some(valid_line)
+}
+
+/// The ip and hosts of a line as written, without the disabled mark and
+/// the comment.
+pub(super) fn line_fields(s: &str) -> &str {
+	let s = s.trim();
+	let s = match s.strip_prefix(HED_COMMENT_MARK) {
+		Some(s) => s,
+		None => s.strip_prefix('#').unwrap_or(s),
+	};
+	split_ip_hosts_comment(s).0.trim()
+}
+
 fn strip_hed_comment(s: &str) -> (&str, bool) {
 	if let Some(striped) = s.strip_prefix(HED_COMMENT_MARK) {
 		(striped, false)
@@ -209,154 +715,213 @@ fn split_ip_hosts(s: &str) -> Option<(String, Vec<String>)> {
 	None
 }
 
-fn lines_to_list(lines: &[Line]) -> Vec<Item> {
-	let mut item_map: IndexMap<String, Item> = IndexMap::new();
+fn lines_to_list<'a>(lines: impl IntoIterator<Item = &'a Line>) -> Vec<Item> {
+	// collect the hosts of each ip first, deduping them once per item keeps
+	// files with thousands of lines for the same ip linear
+	let mut host_map: IndexMap<&str, Vec<Host>> = IndexMap::new();
 
 	for line in lines {
 		if let Line::Valid(valid_line) = line {
-			if let Some(item) = item_map.get_mut(&valid_line.ip) {
-				item.add_hosts(valid_line.hosts.clone(), valid_line.enabled);
-			} else {
-				item_map.insert(
-					valid_line.ip.clone(),
-					Item::new(
-						&valid_line.ip,
-						valid_line.hosts.clone(),
-						valid_line.enabled,
-					),
-				);
-			}
+			host_map.entry(&valid_line.ip).or_default().extend(
+				valid_line.hosts.iter().map(|name| Host {
+					id: GLOBAL_ID.next(),
+					name: name.clone(),
+					enabled: valid_line.enabled,
+				}),
+			);
 		}
 	}
 
-	item_map.into_values().collect()
-}
-
-fn gen_key(ip: &str, enabled: bool) -> String {
-	format!("{}{}", ip, enabled)
-}
-
-fn remove_lines_by_indices(
-	lines: Vec<Line>,
-	indices: &mut HashSet<usize>,
-) -> Vec<Line> {
-	let mut new = vec![];
-	for (i, line) in lines.into_iter().enumerate() {
-		if !indices.contains(&i) {
-			new.push(line);
-		}
-	}
-	indices.clear();
-	new
+	host_map
+		.into_iter()
+		.map(|(ip, hosts)| {
+			let mut item = Item::new(ip, vec![], true);
+			item.push_hosts(hosts);
+			item
+		})
+		.collect()
 }
 
-fn new_lines_by_list(lines: &[Line], list: &[Item]) -> Vec<Line> {
-	let mut lines = lines.to_vec();
-
-	let mut indices_to_removed: HashSet<usize> = HashSet::new();
-
-	let mut ip_enabled_set: HashSet<String> = HashSet::new();
-
-	for (i, line) in lines.iter().enumerate() {
+/// Rebuild the items of `changed_ips` from `lines`, the others are taken
+/// from `list` with their ids.
+fn update_changed_items(
+	list: Vec<Item>,
+	lines: &[Line],
+	changed_ips: &HashSet<&str>,
+) -> Vec<Item> {
+	let mut items: HashMap<String, Item> = list
+		.into_iter()
+		.filter(|item| !changed_ips.contains(item.ip.as_str()))
+		.map(|item| (item.ip.clone(), item))
+		.collect();
+	items.extend(
+		lines_to_list(lines.iter().filter(|line| {
+			matches!(line, Line::Valid(valid_line)
+				if changed_ips.contains(valid_line.ip.as_str()))
+		}))
+		.into_iter()
+		.map(|item| (item.ip.clone(), item)),
+	);
+
+	let mut new_list = vec![];
+	for line in lines {
 		if let Line::Valid(valid_line) = line {
-			let key = gen_key(&valid_line.ip, valid_line.enabled);
-			if ip_enabled_set.contains(&key) {
-				indices_to_removed.insert(i);
-			} else {
-				ip_enabled_set.insert(key);
+			if let Some(item) = items.remove(&valid_line.ip) {
+				new_list.push(item);
 			}
 		}
 	}
 
-	lines = remove_lines_by_indices(lines, &mut indices_to_removed);
+	new_list
+}
 
-	let mut line_idx_map: HashMap<String, usize> = HashMap::new();
+fn new_lines_by_list(lines: &[Line], list: &[Item]) -> Vec<Line> {
+	// the hosts of each ip and state, taken by the first line of them
+	let mut hosts_map: IndexMap<(&str, bool), Option<Vec<String>>> =
+		IndexMap::new();
 
-	for (i, line) in lines.iter().enumerate() {
-		if let Line::Valid(valid_line) = line {
-			line_idx_map.insert(gen_key(&valid_line.ip, valid_line.enabled), i);
+	for item in list {
+		for enabled in [true, false] {
+			hosts_map.entry((&item.ip, enabled)).or_insert(Some(vec![]));
+		}
+		for host in &item.hosts {
+			if let Some(Some(hosts)) =
+				hosts_map.get_mut(&(item.ip.as_str(), host.enabled))
+			{
+				hosts.push(host.name.clone());
+			}
 		}
 	}
 
-	let mut list_ip_set: HashSet<&str> = HashSet::new();
+	let list_host_set: HashSet<(&str, &str)> = list
+		.iter()
+		.flat_map(|item| {
+			item.hosts
+				.iter()
+				.map(|host| (item.ip.as_str(), host.name.as_str()))
+		})
+		.collect();
 
-	for item in list {
-		let mut enabled_hosts = vec![];
-		let mut disabled_hosts = vec![];
+	let mut new = Vec::with_capacity(lines.len());
+	let mut timers_at = None;
 
-		for host in &item.hosts {
-			if host.enabled {
-				enabled_hosts.push(host.name.clone());
-			} else {
-				disabled_hosts.push(host.name.clone());
+	for line in lines {
+		match line {
+			Line::Valid(valid_line) => {
+				let hosts = hosts_map
+					.get_mut(&(valid_line.ip.as_str(), valid_line.enabled))
+					.and_then(Option::take);
+				if let Some(hosts) = hosts.filter(|hosts| !hosts.is_empty()) {
+					new.push(Line::Valid(ValidLine {
+						ip: valid_line.ip.clone(),
+						hosts,
+						comment: valid_line.comment.clone(),
+						enabled: valid_line.enabled,
+					}));
+				}
 			}
-		}
-
-		if let Some(idx) = line_idx_map.get(&gen_key(&item.ip, true)) {
-			if let Some(Line::Valid(valid_line)) = lines.get_mut(*idx) {
-				valid_line.hosts = enabled_hosts;
+			Line::Timer(timer) => {
+				// keep the timers below the entries they belong to
+				timers_at.get_or_insert(new.len());
+				if list_host_set
+					.contains(&(timer.ip.as_str(), timer.host.as_str()))
+				{
+					new.push(line.clone());
+				}
+			}
+			_ => {
+				new.push(line.clone());
 			}
-		} else {
-			lines.push(Line::Valid(ValidLine {
-				ip: item.ip.clone(),
-				hosts: enabled_hosts,
-				comment: None,
-				enabled: true,
-			}));
 		}
+	}
 
-		if let Some(idx) = line_idx_map.get(&gen_key(&item.ip, false)) {
-			if let Some(Line::Valid(valid_line)) = lines.get_mut(*idx) {
-				valid_line.hosts = disabled_hosts;
-			}
-		} else {
-			lines.push(Line::Valid(ValidLine {
-				ip: item.ip.clone(),
-				hosts: disabled_hosts,
+	let added_lines =
+		hosts_map.into_iter().filter_map(|((ip, enabled), hosts)| {
+			let hosts = hosts.filter(|hosts| !hosts.is_empty())?;
+			Some(Line::Valid(ValidLine {
+				ip: ip.to_string(),
+				hosts,
 				comment: None,
-				enabled: false,
-			}));
-		}
+				enabled,
+			}))
+		});
+	let insert_at = timers_at.unwrap_or(new.len());
+	new.splice(insert_at..insert_at, added_lines);
+
+	let mut is_previous_line_empty = true;
+	new.retain(|line| {
+		let is_line_empty = matches!(line, Line::Empty);
+		let keep = !(is_line_empty && is_previous_line_empty);
+		is_previous_line_empty = is_line_empty;
+		keep
+	});
 
-		list_ip_set.insert(&item.ip);
+	if new.last().is_some_and(|line| !matches!(line, Line::Empty)) {
+		new.push(Line::Empty);
 	}
 
-	for (i, line) in lines.iter().enumerate() {
-		if let Line::Valid(valid_line) = line {
-			if valid_line.hosts.is_empty()
-				|| !list_ip_set.contains(valid_line.ip.as_str())
-			{
-				indices_to_removed.insert(i);
-			}
-		}
-	}
+	new
+}
 
-	lines = remove_lines_by_indices(lines, &mut indices_to_removed);
+/// Sort valid lines by the position of their ip in `list`. Comment lines
+/// directly above a valid line move together with it, everything else stays
+/// in place.
+fn reorder_lines(lines: &[Line], list: &[Item]) -> Vec<Line> {
+	let ip_order: HashMap<&str, usize> = list
+		.iter()
+		.enumerate()
+		.map(|(i, item)| (item.ip.as_str(), i))
+		.collect();
+
+	reorder_lines_by(lines, &ip_order)
+}
 
-	let mut is_previous_line_empty = if lines.is_empty() {
-		false
-	} else {
-		matches!(lines[0], Line::Empty)
-	};
+/// Like [`reorder_lines`], with the position of each ip given by `ip_order`.
+pub(super) fn reorder_lines_by(
+	lines: &[Line],
+	ip_order: &HashMap<&str, usize>,
+) -> Vec<Line> {
+	let mut segments: Vec<Option<Line>> = vec![];
+	let mut blocks: Vec<(usize, Vec<Line>)> = vec![];
+	let mut comments: Vec<Line> = vec![];
 
-	for (i, line) in lines.iter().enumerate() {
-		let is_line_empty = matches!(line, Line::Empty);
-		if is_line_empty && is_previous_line_empty {
-			indices_to_removed.insert(i);
+	for line in lines {
+		match line {
+			Line::Comment(_) => {
+				comments.push(line.clone());
+			}
+			Line::Valid(valid_line) => {
+				let order = ip_order
+					.get(valid_line.ip.as_str())
+					.copied()
+					.unwrap_or(usize::MAX);
+				let mut block = std::mem::take(&mut comments);
+				block.push(line.clone());
+				blocks.push((order, block));
+				segments.push(None);
+			}
+			_ => {
+				segments.extend(comments.drain(..).map(Some));
+				segments.push(Some(line.clone()));
+			}
 		}
-		is_previous_line_empty = is_line_empty;
 	}
 
-	lines = remove_lines_by_indices(lines, &mut indices_to_removed);
+	segments.extend(comments.into_iter().map(Some));
+	blocks.sort_by_key(|(order, _)| *order);
+
+	let mut blocks = blocks.into_iter();
+	let mut new = vec![];
 
-	if lines
-		.last()
-		.is_some_and(|line| !matches!(line, Line::Empty))
-	{
-		lines.push(Line::Empty);
+	for segment in segments {
+		if let Some(line) = segment {
+			new.push(line);
+		} else if let Some((_, mut block)) = blocks.next() {
+			new.append(&mut block);
+		}
 	}
 
-	lines
+	new
 }
 
 #[cfg(test)]
@@ -368,6 +933,11 @@ mod tests {
 
 	use super::{
 		content_to_lines, lines_to_content, lines_to_list, new_lines_by_list,
+		reorder_lines, FileFormat, HostsInfo,
+	};
+	use crate::core::{
+		file_format::Eol,
+		hosts_options::{DisabledMark, FormatOptions, HostsOptions, Separator},
 	};
 
 	#[fixture]
@@ -378,38 +948,53 @@ mod tests {
 
 	#[rstest]
 	fn test_content_to_lines(content: String) {
-		let lines = content_to_lines(&content);
+		let lines = content_to_lines(&content, &HostsOptions::default());
 
 		assert_debug_snapshot!("content_to_lines", lines);
 	}
 
 	#[rstest]
 	fn test_lines_to_content_win(content: String) {
-		let lines = content_to_lines(&content);
-		let new_content_win = lines_to_content(&lines, true);
+		let lines = content_to_lines(&content, &HostsOptions::default());
+		let new_content_win = lines_to_content(
+			&lines,
+			&FileFormat {
+				eol: Eol::CrLf,
+				..Default::default()
+			},
+			&HostsOptions::default(),
+		);
 
 		assert_debug_snapshot!("lines_to_content_win", new_content_win);
 	}
 
 	#[rstest]
 	fn test_lines_to_content_mac(content: String) {
-		let lines = content_to_lines(&content);
-		let new_content_mac = lines_to_content(&lines, false);
+		let lines = content_to_lines(&content, &HostsOptions::default());
+		let new_content_mac = lines_to_content(
+			&lines,
+			&FileFormat::default(),
+			&HostsOptions::default(),
+		);
 
 		assert_debug_snapshot!("lines_to_content_mac", new_content_mac);
 	}
 
 	#[rstest]
 	fn test_lines_to_content_human_read(content: String) {
-		let lines = content_to_lines(&content);
-		let new_content = lines_to_content(&lines, false);
+		let lines = content_to_lines(&content, &HostsOptions::default());
+		let new_content = lines_to_content(
+			&lines,
+			&FileFormat::default(),
+			&HostsOptions::default(),
+		);
 
 		assert_snapshot!("lines_to_content_human_read", new_content);
 	}
 
 	#[rstest]
 	fn test_lines_to_list(content: String) {
-		let lines = content_to_lines(&content);
+		let lines = content_to_lines(&content, &HostsOptions::default());
 		let list = lines_to_list(&lines);
 
 		assert_debug_snapshot!("lines_to_list", list);
@@ -417,7 +1002,7 @@ mod tests {
 
 	#[rstest]
 	fn test_new_lines_by_list(content: String) {
-		let mut lines = content_to_lines(&content);
+		let mut lines = content_to_lines(&content, &HostsOptions::default());
 		let mut list = lines_to_list(&lines);
 
 		for (i, hosts) in list.iter_mut().enumerate() {
@@ -428,4 +1013,228 @@ mod tests {
 
 		assert_debug_snapshot!("new_lines_by_list", lines);
 	}
+
+	#[test]
+	fn test_reorder_lines() {
+		let content = "# header\n\n# a\n10.0.0.1 a.com\n# b\n# b2\n\
+		               10.0.0.2 b.com\n\n10.0.0.3 c.com\n#(hed) 10.0.0.1 d.com\n";
+		let lines = content_to_lines(content, &HostsOptions::default());
+		let mut list = lines_to_list(&lines);
+		list.rotate_left(1);
+
+		let lines = reorder_lines(&lines, &list);
+
+		assert_snapshot!(
+			"reorder_lines",
+			lines_to_content(
+				&lines,
+				&FileFormat::default(),
+				&HostsOptions::default()
+			)
+		);
+	}
+
+	#[test]
+	fn test_commented_lines() {
+		let content =
+			"# 127.0.0.1 foo.test # note\n#(hed) 127.0.0.1 bar.test\n\
+		               # just a comment\n## 127.0.0.1 baz.test\n";
+		let options = HostsOptions {
+			parse_commented: true,
+			disabled_mark: DisabledMark::Plain,
+			..Default::default()
+		};
+		let lines = content_to_lines(content, &options);
+
+		assert_debug_snapshot!(
+			"commented_lines_to_list",
+			lines_to_list(&lines)
+		);
+		assert_snapshot!(
+			"commented_lines_to_content",
+			lines_to_content(&lines, &FileFormat::default(), &options)
+		);
+	}
+
+	#[test]
+	fn test_keep_trailing_newline() {
+		let mut hosts_info = HostsInfo {
+			content: "127.0.0.1 a.test".to_string(),
+			file_format: FileFormat {
+				trailing_newline: false,
+				..Default::default()
+			},
+			..Default::default()
+		};
+		hosts_info.update_list();
+		hosts_info.list[0].add_hosts(vec!["b.test".to_string()], true);
+		hosts_info.update_content();
+
+		assert_eq!(hosts_info.content, "127.0.0.1 a.test b.test");
+	}
+
+	#[test]
+	fn test_expire_timers() {
+		let content = "127.0.0.1 a.test b.test\n#(hed) 10.0.0.1 c.test\n\n\
+		               #(hed:timer) 127.0.0.1 a.test 100 disable\n\
+		               #(hed:timer) 10.0.0.1 c.test 300 enable\n\
+		               #(hed:timer) 10.0.0.1 gone.test 100 enable\n";
+		let mut hosts_info = HostsInfo {
+			content: content.to_string(),
+			..Default::default()
+		};
+		hosts_info.update_list();
+
+		assert_eq!(hosts_info.timers().count(), 3);
+		assert!(!hosts_info.has_expired_timers(99));
+		assert!(hosts_info.has_expired_timers(200));
+		assert!(!hosts_info.expire_timers(99));
+		assert!(hosts_info.expire_timers(200));
+		assert_eq!(hosts_info.timers().count(), 1);
+		assert_snapshot!("expire_timers", hosts_info.content);
+	}
+
+	#[rstest]
+	#[case::edit_host("10.0.0.1 a.test b.test", "10.0.0.1 a.test c.test")]
+	#[case::comment_out("10.0.0.2 d.test", "#(hed) 10.0.0.2 d.test")]
+	#[case::new_ip("# note", "10.0.0.9 z.test")]
+	#[case::delete("10.0.0.1 e.test\n", "")]
+	#[case::comment("# note", "# changed")]
+	fn test_update_list_by_edit(#[case] from: &str, #[case] to: &str) {
+		let content = "# note\n10.0.0.1 a.test b.test\n10.0.0.2 d.test\n\
+		               10.0.0.1 e.test\n#(hed) 10.0.0.3 f.test\n";
+		let mut hosts_info = HostsInfo {
+			content: content.to_string(),
+			..Default::default()
+		};
+		hosts_info.update_list();
+		hosts_info.content = content.replacen(from, to, 1);
+		hosts_info.update_list_by_edit();
+
+		let lines = content_to_lines(&hosts_info.content, &hosts_info.options);
+		assert_eq!(
+			format!("{:?}", hosts_info.list),
+			format!("{:?}", lines_to_list(&lines))
+		);
+		assert_eq!(format!("{:?}", hosts_info.lines), format!("{:?}", lines));
+	}
+
+	#[rstest]
+	#[case::align("format_align", FormatOptions {
+		align_ip: true,
+		..Default::default()
+	})]
+	#[case::max_hosts("format_max_hosts", FormatOptions {
+		max_hosts_per_line: 2,
+		..Default::default()
+	})]
+	#[case::max_length("format_max_length", FormatOptions {
+		max_line_length: 24,
+		..Default::default()
+	})]
+	#[case::one_host("format_one_host", FormatOptions {
+		one_host_per_line: true,
+		separator: Separator::Tab,
+		..Default::default()
+	})]
+	fn test_lines_to_content_format(
+		#[case] name: &str,
+		#[case] format: FormatOptions,
+	) {
+		let content = "127.0.0.1 a.com b.com c.com # note\n\
+		               #(hed) 2402:1200::1 d.com e.com\n";
+		let options = HostsOptions {
+			format,
+			..Default::default()
+		};
+		let lines = content_to_lines(content, &options);
+
+		assert_snapshot!(
+			name,
+			lines_to_content(&lines, &FileFormat::default(), &options)
+		);
+	}
+
+	/// The list of `content` with distinct ids, item `i` and its hosts
+	/// `10 * i + j`.
+	fn hosts_info_with_ids(content: &str) -> HostsInfo {
+		let mut hosts_info = HostsInfo {
+			content: content.to_string(),
+			..Default::default()
+		};
+		hosts_info.update_list();
+		for (i, item) in hosts_info.list.iter_mut().enumerate() {
+			item.id = i;
+			for (j, host) in item.hosts.iter_mut().enumerate() {
+				host.id = 10 * i + j;
+			}
+		}
+		hosts_info
+	}
+
+	fn names(hosts_info: &HostsInfo) -> Vec<(String, Vec<String>)> {
+		hosts_info
+			.list
+			.iter()
+			.map(|item| {
+				let hosts = item.hosts.iter().map(|h| h.name.clone()).collect();
+				(item.ip.clone(), hosts)
+			})
+			.collect()
+	}
+
+	#[rstest]
+	#[case::move_to_new(true, "10.0.0.9", vec![
+		("10.0.0.1", vec!["b.test"]),
+		("10.0.0.2", vec!["d.test"]),
+		("10.0.0.9", vec!["a.test", "c.test"]),
+	])]
+	#[case::move_to_existing(true, "10.0.0.2", vec![
+		("10.0.0.1", vec!["b.test"]),
+		("10.0.0.2", vec!["c.test", "d.test", "a.test"]),
+	])]
+	#[case::copy_to_new(false, "10.0.0.9", vec![
+		("10.0.0.1", vec!["a.test", "b.test"]),
+		("10.0.0.2", vec!["c.test", "d.test"]),
+		("10.0.0.9", vec!["a.test", "c.test"]),
+	])]
+	#[case::copy_to_existing(false, "10.0.0.2", vec![
+		("10.0.0.1", vec!["a.test", "b.test"]),
+		("10.0.0.2", vec!["c.test", "d.test", "a.test"]),
+	])]
+	fn test_transfer_hosts(
+		#[case] remove: bool,
+		#[case] ip: &str,
+		#[case] expected: Vec<(&str, Vec<&str>)>,
+	) {
+		let mut hosts_info = hosts_info_with_ids(
+			"10.0.0.1 a.test b.test\n10.0.0.2 c.test d.test\n",
+		);
+
+		hosts_info.transfer_hosts(&[(0, 0), (1, 10)], ip, remove);
+
+		let expected: Vec<(String, Vec<String>)> = expected
+			.into_iter()
+			.map(|(ip, hosts)| {
+				(
+					ip.to_string(),
+					hosts.into_iter().map(String::from).collect(),
+				)
+			})
+			.collect();
+		assert_eq!(names(&hosts_info), expected);
+	}
+
+	#[test]
+	fn test_remove_hosts() {
+		let mut hosts_info =
+			hosts_info_with_ids("10.0.0.1 a.test b.test\n10.0.0.2 c.test\n");
+
+		hosts_info.remove_hosts(&[(0, 1), (1, 10)]);
+
+		assert_eq!(
+			names(&hosts_info),
+			[("10.0.0.1".to_string(), vec!["a.test".to_string()])]
+		);
+	}
 }
@@ -0,0 +1,73 @@
+/// Options that control how a hosts file is parsed and written back.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostsOptions {
+	/// Parse `# ip host…` lines as disabled hosts instead of comments.
+	pub parse_commented: bool,
+	pub disabled_mark: DisabledMark,
+	pub format: FormatOptions,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DisabledMark {
+	#[default]
+	Hed,
+	Plain,
+}
+
+impl DisabledMark {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			DisabledMark::Hed => "#(hed)",
+			DisabledMark::Plain => "#",
+		}
+	}
+}
+
+/// Layout of valid lines written by [`super::HostsInfo::update_content`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatOptions {
+	/// Pad the ip column so that hosts start at the same column.
+	pub align_ip: bool,
+	/// Split lines holding more hosts than this, `0` means no limit.
+	pub max_hosts_per_line: usize,
+	/// Split lines longer than this, `0` means no limit.
+	pub max_line_length: usize,
+	pub one_host_per_line: bool,
+	pub separator: Separator,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Separator {
+	#[default]
+	Space,
+	Tab,
+}
+
+impl FormatOptions {
+	pub fn hosts_limit(&self) -> usize {
+		if self.one_host_per_line {
+			1
+		} else if self.max_hosts_per_line == 0 {
+			usize::MAX
+		} else {
+			self.max_hosts_per_line
+		}
+	}
+
+	pub fn length_limit(&self) -> usize {
+		if self.max_line_length == 0 {
+			usize::MAX
+		} else {
+			self.max_line_length
+		}
+	}
+}
+
+impl Separator {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Separator::Space => " ",
+			Separator::Tab => "\t",
+		}
+	}
+}
@@ -62,6 +62,29 @@ impl Item {
 		self.hosts_dedup();
 	}
 
+	pub fn merge(&mut self, other: Item) {
+		self.push_hosts(other.hosts);
+	}
+
+	pub fn push_hosts(&mut self, hosts: Vec<Host>) {
+		self.hosts.extend(hosts);
+		self.hosts_dedup();
+	}
+
+	pub fn take_host(&mut self, host_id: usize) -> Option<(usize, Host)> {
+		let idx = self.hosts.iter().position(|host| host.id == host_id)?;
+		Some((idx, self.hosts.remove(idx)))
+	}
+
+	pub fn insert_host(&mut self, idx: usize, host: Host) {
+		self.hosts.insert(idx.min(self.hosts.len()), host);
+		self.hosts_dedup();
+	}
+
+	pub fn get_host(&self, host_id: usize) -> Option<&Host> {
+		self.hosts.iter().find(|host| host.id == host_id)
+	}
+
 	pub fn contains(&self, s: &str) -> bool {
 		self.ip.contains(s)
 			|| self.hosts.iter().any(|host| host.name.contains(s))
@@ -1,4 +1,7 @@
-use crate::util::{is_ip, StringExt};
+use crate::{
+	i18n::{t, tf},
+	util::{is_ip, StringExt},
+};
 
 #[derive(Default)]
 pub struct ItemForm {
@@ -17,22 +20,26 @@ impl ItemForm {
 	}
 
 	pub fn validate(&mut self) -> bool {
+		self.validate_ip() && self.validate_hosts()
+	}
+
+	pub fn validate_ip(&mut self) -> bool {
 		self.ip.make_trim();
 		if self.ip.is_empty() {
-			self.ip_error = "IP address is empty".to_string();
+			self.ip_error = t("error.ip_empty").to_string();
 			return false;
 		}
 		if !is_ip(&self.ip) {
-			self.ip_error = format!("`{}` is not a valid IP address", self.ip);
+			self.ip_error = tf("error.ip_invalid", &[&self.ip]);
 			return false;
 		}
-		self.validate_hosts()
+		true
 	}
 
 	pub fn validate_hosts(&mut self) -> bool {
 		self.hosts.make_trim();
 		if self.hosts.is_empty() {
-			self.hosts_error = "hosts is empty".to_string();
+			self.hosts_error = t("error.hosts_empty").to_string();
 			return false;
 		}
 		true
@@ -0,0 +1,270 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+use super::command::Command;
+use crate::i18n::{t, tf};
+
+/// Shortcuts used by text editing, binding them would break the text view.
+const RESERVED: [(Modifiers, Key); 6] = [
+	(Modifiers::CTRL, Key::A),
+	(Modifiers::CTRL, Key::C),
+	(Modifiers::CTRL, Key::V),
+	(Modifiers::CTRL, Key::X),
+	(Modifiers::CTRL, Key::Y),
+	(Modifiers::CTRL, Key::Z),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+	bindings: Vec<(Command, Option<KeyboardShortcut>)>,
+}
+
+impl Default for Keymap {
+	fn default() -> Self {
+		let bindings = Command::BINDABLE
+			.into_iter()
+			.map(|command| {
+				let shortcut =
+					default_shortcut(&command).map(|(modifiers, key)| {
+						KeyboardShortcut::new(modifiers, key)
+					});
+				(command, shortcut)
+			})
+			.collect();
+
+		Self { bindings }
+	}
+}
+
+impl Keymap {
+	pub fn get(&self, command: &Command) -> Option<KeyboardShortcut> {
+		self.bindings
+			.iter()
+			.find(|(c, _)| c == command)
+			.and_then(|(_, shortcut)| *shortcut)
+	}
+
+	pub fn set(
+		&mut self,
+		command: &Command,
+		shortcut: Option<KeyboardShortcut>,
+	) {
+		if let Some((_, s)) =
+			self.bindings.iter_mut().find(|(c, _)| c == command)
+		{
+			*s = shortcut;
+		}
+	}
+
+	pub fn iter(
+		&self,
+	) -> impl Iterator<Item = (&Command, Option<KeyboardShortcut>)> {
+		self.bindings
+			.iter()
+			.map(|(command, shortcut)| (command, *shortcut))
+	}
+
+	/// Why the shortcut of `command` can not be used, if it can't.
+	pub fn conflict(&self, command: &Command) -> Option<String> {
+		let shortcut = self.get(command)?;
+
+		if RESERVED.contains(&(shortcut.modifiers, shortcut.logical_key)) {
+			return Some(t("keymap.reserved").to_string());
+		}
+
+		// either this shortcut runs another command, or another one runs
+		// this command
+		let shadowed_by = self
+			.command_for(shortcut.modifiers, shortcut.logical_key)
+			.filter(|c| *c != command);
+		let shadows = || {
+			self.bindings.iter().find_map(|(c, s)| {
+				let s = s.as_ref().filter(|_| c != command)?;
+				(self.command_for(s.modifiers, s.logical_key) == Some(command))
+					.then_some(c)
+			})
+		};
+
+		shadowed_by
+			.or_else(shadows)
+			.map(|c| tf("keymap.conflict", &[&c.label()]))
+	}
+
+	/// Bindings with more modifiers first. A press matches every shortcut
+	/// whose modifiers it logically includes, so Ctrl+Shift+S must be tried
+	/// before Ctrl+S takes it.
+	pub fn by_priority(&self) -> Vec<(&Command, KeyboardShortcut)> {
+		let mut bindings: Vec<(&Command, KeyboardShortcut)> = self
+			.bindings
+			.iter()
+			.filter_map(|(command, shortcut)| Some((command, (*shortcut)?)))
+			.collect();
+		bindings.sort_by_key(|(_, shortcut)| {
+			std::cmp::Reverse(modifier_count(shortcut.modifiers))
+		});
+		bindings
+	}
+
+	/// The command a press of `modifiers` and `key` runs.
+	pub fn command_for(
+		&self,
+		modifiers: Modifiers,
+		key: Key,
+	) -> Option<&Command> {
+		self.by_priority()
+			.into_iter()
+			.find(|(_, shortcut)| {
+				shortcut.logical_key == key
+					&& modifiers.matches_logically(shortcut.modifiers)
+			})
+			.map(|(command, _)| command)
+	}
+
+	pub fn has_conflict(&self) -> bool {
+		self.bindings
+			.iter()
+			.any(|(command, _)| self.conflict(command).is_some())
+	}
+}
+
+fn default_shortcut(command: &Command) -> Option<(Modifiers, Key)> {
+	let shortcut = match command {
+		Command::Save => (Modifiers::CTRL, Key::S),
+		Command::Reset => (Modifiers::CTRL | Modifiers::SHIFT, Key::R),
+		Command::Reload => (Modifiers::NONE, Key::F5),
+		Command::OpenFile => (Modifiers::CTRL, Key::O),
+		Command::SaveAs => (Modifiers::CTRL | Modifiers::SHIFT, Key::S),
+		Command::CloseTab => (Modifiers::CTRL, Key::W),
+		Command::Format => (Modifiers::SHIFT | Modifiers::ALT, Key::F),
+		Command::NewItem => (Modifiers::CTRL, Key::N),
+		Command::FindReplace => (Modifiers::CTRL, Key::H),
+		Command::OpenSettings => (Modifiers::CTRL, Key::Comma),
+		Command::OpenCommandPalette => {
+			(Modifiers::CTRL | Modifiers::SHIFT, Key::P)
+		}
+		_ => return None,
+	};
+
+	Some(shortcut)
+}
+
+pub fn shortcut_to_string(shortcut: &KeyboardShortcut) -> String {
+	let modifiers = shortcut.modifiers;
+	let mut parts = vec![];
+
+	if modifiers.ctrl {
+		parts.push("Ctrl");
+	}
+	if modifiers.mac_cmd {
+		parts.push("Cmd");
+	}
+	if modifiers.command && !modifiers.ctrl && !modifiers.mac_cmd {
+		parts.push("Command");
+	}
+	if modifiers.alt {
+		parts.push("Alt");
+	}
+	if modifiers.shift {
+		parts.push("Shift");
+	}
+	parts.push(shortcut.logical_key.name());
+
+	parts.join("+")
+}
+
+fn modifier_count(modifiers: Modifiers) -> usize {
+	[
+		modifiers.alt,
+		modifiers.ctrl,
+		modifiers.shift,
+		modifiers.mac_cmd,
+		modifiers.command,
+	]
+	.into_iter()
+	.filter(|&pressed| pressed)
+	.count()
+}
+
+pub fn parse_shortcut(s: &str) -> Option<KeyboardShortcut> {
+	let s = s.trim();
+	let (modifiers_part, key_part) = match s.rsplit_once('+') {
+		Some((rest, "")) => (rest.strip_suffix('+').unwrap_or(rest), "+"),
+		Some((rest, key)) => (rest, key),
+		None => ("", s),
+	};
+
+	let mut modifiers = Modifiers::NONE;
+	for part in modifiers_part.split('+').filter(|p| !p.is_empty()) {
+		modifiers = modifiers
+			| match part.trim().to_ascii_lowercase().as_str() {
+				"ctrl" => Modifiers::CTRL,
+				"cmd" => Modifiers::MAC_CMD,
+				"command" => Modifiers::COMMAND,
+				"alt" => Modifiers::ALT,
+				"shift" => Modifiers::SHIFT,
+				_ => return None,
+			};
+	}
+
+	Key::from_name(key_part.trim())
+		.map(|key| KeyboardShortcut::new(modifiers, key))
+}
+
+#[cfg(test)]
+mod tests {
+	use egui::{Key, KeyboardShortcut, Modifiers};
+
+	use super::{parse_shortcut, shortcut_to_string, Keymap};
+	use crate::core::command::Command;
+
+	#[test]
+	fn test_shortcut_string_round_trip() {
+		for shortcut in Keymap::default().iter().filter_map(|(_, s)| s) {
+			let s = shortcut_to_string(&shortcut);
+			assert_eq!(parse_shortcut(&s), Some(shortcut), "{}", s);
+		}
+		assert_eq!(
+			parse_shortcut("Ctrl++"),
+			Some(KeyboardShortcut::new(Modifiers::CTRL, Key::Plus))
+		);
+		assert_eq!(parse_shortcut("Hyper+S"), None);
+	}
+
+	#[test]
+	fn test_keymap_conflict() {
+		let mut keymap = Keymap::default();
+		assert!(!keymap.has_conflict());
+
+		keymap.set(&Command::Reload, keymap.get(&Command::Save));
+		assert!(keymap.conflict(&Command::Reload).is_some());
+		assert!(keymap.conflict(&Command::Save).is_some());
+
+		keymap.set(
+			&Command::Reload,
+			Some(KeyboardShortcut::new(Modifiers::CTRL, Key::C)),
+		);
+		assert!(keymap.conflict(&Command::Reload).is_some());
+		assert!(keymap.conflict(&Command::Save).is_none());
+
+		// Ctrl+Shift+R is tried before Ctrl+R, both stay reachable
+		keymap.set(
+			&Command::Reload,
+			Some(KeyboardShortcut::new(Modifiers::CTRL, Key::R)),
+		);
+		assert!(keymap.conflict(&Command::Reset).is_none());
+		assert!(keymap.conflict(&Command::Reload).is_none());
+	}
+
+	#[test]
+	fn test_command_for_more_modifiers_first() {
+		let keymap = Keymap::default();
+
+		assert_eq!(
+			keymap.command_for(Modifiers::CTRL | Modifiers::SHIFT, Key::S),
+			Some(&Command::SaveAs)
+		);
+		assert_eq!(
+			keymap.command_for(Modifiers::CTRL, Key::S),
+			Some(&Command::Save)
+		);
+	}
+}
@@ -0,0 +1,389 @@
+use super::{
+	hosts_info::{format_valid_line, line_fields, parse_line, Line, ValidLine},
+	hosts_options::HostsOptions,
+};
+use crate::i18n::{t, tf};
+
+/// Windows ignores the hosts past this many on a line.
+const WINDOWS_MAX_HOSTS_PER_LINE: usize = 9;
+/// Windows drops lines longer than this.
+const WINDOWS_MAX_LINE_LENGTH: usize = 255;
+
+const IPV6_LOCALHOST: &str = "::1";
+
+/// Whose quirks the hosts file is checked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+	Windows,
+	MacOs,
+	Linux,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintLevel {
+	Info,
+	Warning,
+	Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintRule {
+	/// Neither a comment nor an ip followed by hosts.
+	InvalidLine,
+	MissingIpv6Localhost,
+	/// Fields separated by both tabs and spaces.
+	MixedSeparators,
+	TooManyHosts(usize),
+	LineTooLong(usize),
+	/// A `.local` host, looked up through mDNS first on macOS.
+	MdnsHost(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintFix {
+	/// Replace the line with these lines.
+	ReplaceLine(Vec<String>),
+	AppendLine(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+	pub rule: LintRule,
+	/// Index of the line, `None` for the whole file.
+	pub line: Option<usize>,
+	/// The ip of the line, if it is a valid one.
+	pub ip: Option<String>,
+	pub fix: Option<LintFix>,
+}
+
+impl Platform {
+	pub const ALL: [Platform; 3] =
+		[Platform::Windows, Platform::MacOs, Platform::Linux];
+
+	pub fn current() -> Self {
+		if cfg!(target_os = "windows") {
+			Platform::Windows
+		} else if cfg!(target_os = "macos") {
+			Platform::MacOs
+		} else {
+			Platform::Linux
+		}
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Platform::Windows => "Windows",
+			Platform::MacOs => "macOS",
+			Platform::Linux => "Linux",
+		}
+	}
+
+	/// Stable name used in the settings file.
+	pub fn id(&self) -> &'static str {
+		match self {
+			Platform::Windows => "windows",
+			Platform::MacOs => "macos",
+			Platform::Linux => "linux",
+		}
+	}
+
+	pub fn from_id(id: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|platform| platform.id() == id)
+	}
+
+	fn limits_lines(&self) -> bool {
+		*self == Platform::Windows
+	}
+
+	fn resolves_mdns(&self) -> bool {
+		*self == Platform::MacOs
+	}
+}
+
+impl LintRule {
+	pub fn level(&self) -> LintLevel {
+		match self {
+			LintRule::InvalidLine => LintLevel::Error,
+			LintRule::TooManyHosts(_)
+			| LintRule::LineTooLong(_)
+			| LintRule::MissingIpv6Localhost => LintLevel::Warning,
+			LintRule::MixedSeparators | LintRule::MdnsHost(_) => {
+				LintLevel::Info
+			}
+		}
+	}
+
+	pub fn message(&self) -> String {
+		match self {
+			LintRule::InvalidLine => t("lint.invalid_line").to_string(),
+			LintRule::MissingIpv6Localhost => {
+				t("lint.missing_ipv6_localhost").to_string()
+			}
+			LintRule::MixedSeparators => t("lint.mixed_separators").to_string(),
+			LintRule::TooManyHosts(count) => {
+				tf("lint.too_many_hosts", &[count, &WINDOWS_MAX_HOSTS_PER_LINE])
+			}
+			LintRule::LineTooLong(len) => {
+				tf("lint.line_too_long", &[len, &WINDOWS_MAX_LINE_LENGTH])
+			}
+			LintRule::MdnsHost(host) => tf("lint.mdns_host", &[host]),
+		}
+	}
+}
+
+/// Check `content` for what breaks on `platform`, line by line.
+pub fn lint(
+	content: &str,
+	options: &HostsOptions,
+	platform: Platform,
+) -> Vec<Lint> {
+	let mut lints = vec![];
+	let mut has_ipv6_localhost = false;
+
+	for (idx, text) in content.lines().enumerate() {
+		let valid_line = match parse_line(text, options) {
+			Line::Valid(valid_line) => valid_line,
+			Line::Other(_) => {
+				lints.push(Lint {
+					rule: LintRule::InvalidLine,
+					line: Some(idx),
+					ip: None,
+					fix: Some(LintFix::ReplaceLine(vec![format!(
+						"# {}",
+						text.trim()
+					)])),
+				});
+				continue;
+			}
+			_ => continue,
+		};
+		let mut push = |rule: LintRule, fix: Option<LintFix>| {
+			lints.push(Lint {
+				rule,
+				line: Some(idx),
+				ip: Some(valid_line.ip.clone()),
+				fix,
+			});
+		};
+
+		// the mark of a disabled line and a comment may use another one
+		let fields = line_fields(text);
+		if fields.contains('\t') && fields.contains(' ') {
+			let fixed = format_valid_line(&valid_line, options, 0);
+			push(LintRule::MixedSeparators, Some(LintFix::ReplaceLine(fixed)));
+		}
+
+		if !valid_line.enabled {
+			continue;
+		}
+		if valid_line.ip == IPV6_LOCALHOST
+			&& valid_line.hosts.iter().any(|host| host == "localhost")
+		{
+			has_ipv6_localhost = true;
+		}
+		if platform.limits_lines() {
+			let hosts = valid_line.hosts.len();
+			let len = text.chars().count();
+			if hosts > WINDOWS_MAX_HOSTS_PER_LINE {
+				let fix = split_line(&valid_line, options);
+				push(LintRule::TooManyHosts(hosts), Some(fix));
+			} else if len > WINDOWS_MAX_LINE_LENGTH {
+				let fix = split_line(&valid_line, options);
+				push(LintRule::LineTooLong(len), Some(fix));
+			}
+		}
+		if platform.resolves_mdns() {
+			for host in &valid_line.hosts {
+				if host.to_lowercase().ends_with(".local") {
+					push(LintRule::MdnsHost(host.clone()), None);
+				}
+			}
+		}
+	}
+
+	if !has_ipv6_localhost {
+		let sep = options.format.separator.as_str();
+		lints.push(Lint {
+			rule: LintRule::MissingIpv6Localhost,
+			line: None,
+			ip: None,
+			fix: Some(LintFix::AppendLine(format!(
+				"{}{}localhost",
+				IPV6_LOCALHOST, sep
+			))),
+		});
+	}
+
+	lints
+}
+
+/// Split a line within the limits of Windows.
+fn split_line(valid_line: &ValidLine, options: &HostsOptions) -> LintFix {
+	let mut options = options.clone();
+	let format = &mut options.format;
+	format.max_hosts_per_line =
+		format.hosts_limit().min(WINDOWS_MAX_HOSTS_PER_LINE);
+	format.max_line_length = format.length_limit().min(WINDOWS_MAX_LINE_LENGTH);
+
+	let lines = format_valid_line(valid_line, &options, 0);
+	if lines
+		.iter()
+		.all(|line| line.chars().count() <= WINDOWS_MAX_LINE_LENGTH)
+	{
+		return LintFix::ReplaceLine(lines);
+	}
+
+	// a comment too long to share a line with a host goes above the line
+	let mut valid_line = valid_line.clone();
+	let comment = valid_line.comment.take();
+	let mut lines = format_valid_line(&valid_line, &options, 0);
+	if let Some(comment) = comment {
+		lines.insert(0, format!("# {}", comment));
+	}
+	LintFix::ReplaceLine(lines)
+}
+
+/// `content` with the fix of `lint` applied, lines are joined by `eol`.
+pub fn apply_fix(content: &str, eol: &str, lint: &Lint) -> String {
+	let Some(fix) = &lint.fix else {
+		return content.to_string();
+	};
+	let mut lines: Vec<String> = content.lines().map(String::from).collect();
+	let trailing_newline = content.ends_with('\n');
+
+	match (fix, lint.line) {
+		(LintFix::ReplaceLine(new_lines), Some(idx)) if idx < lines.len() => {
+			lines.splice(idx..=idx, new_lines.iter().cloned());
+		}
+		(LintFix::AppendLine(line), _) => {
+			lines.push(line.clone());
+		}
+		_ => {}
+	}
+
+	let mut fixed = lines.join(eol);
+	if trailing_newline {
+		fixed.push_str(eol);
+	}
+	fixed
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::{apply_fix, lint, LintRule, Platform};
+	use crate::core::{HostsOptions, Separator};
+
+	fn rules(content: &str, platform: Platform) -> Vec<LintRule> {
+		lint(content, &HostsOptions::default(), platform)
+			.into_iter()
+			.map(|lint| lint.rule)
+			.collect()
+	}
+
+	#[rstest]
+	#[case(Platform::Windows, vec![LintRule::TooManyHosts(10)])]
+	#[case(Platform::MacOs, vec![LintRule::MdnsHost("h.local".into())])]
+	#[case(Platform::Linux, vec![])]
+	fn test_platform_rules(
+		#[case] platform: Platform,
+		#[case] expected: Vec<LintRule>,
+	) {
+		let content = "::1 localhost\n\
+			127.0.0.1 a b c d e f g h i h.local\n";
+
+		assert_eq!(rules(content, platform), expected);
+	}
+
+	#[test]
+	fn test_lint_lines() {
+		let content = "not a line\n127.0.0.1\ta b\n";
+
+		assert_eq!(
+			rules(content, Platform::Linux),
+			[
+				LintRule::InvalidLine,
+				LintRule::MixedSeparators,
+				LintRule::MissingIpv6Localhost,
+			]
+		);
+	}
+
+	#[test]
+	fn test_apply_fix() {
+		let content = "127.0.0.1 a b c d e f g h i j\r\n# end\r\n";
+		let options = HostsOptions::default();
+		let lints = lint(content, &options, Platform::Windows);
+
+		let fixed = apply_fix(content, "\r\n", &lints[0]);
+		assert_eq!(
+			fixed,
+			"127.0.0.1 a b c d e f g h i\r\n127.0.0.1 j\r\n# end\r\n"
+		);
+
+		let fixed = apply_fix(&fixed, "\r\n", &lints[1]);
+		assert!(fixed.ends_with("# end\r\n::1 localhost\r\n"));
+		assert!(lint(&fixed, &options, Platform::Windows).is_empty());
+	}
+
+	#[test]
+	fn test_split_long_line_with_comment() {
+		let hosts: Vec<String> = (0..8)
+			.map(|i| format!("{}{}.test", i, "h".repeat(20)))
+			.collect();
+		let content = format!(
+			"::1 localhost\n127.0.0.1 {} # {}\n",
+			hosts.join(" "),
+			"c".repeat(60)
+		);
+		let options = HostsOptions::default();
+		let lints = lint(&content, &options, Platform::Windows);
+		assert!(matches!(lints[0].rule, LintRule::LineTooLong(_)));
+
+		let fixed = apply_fix(&content, "\n", &lints[0]);
+		assert!(fixed.lines().all(|line| line.chars().count() <= 255));
+		assert!(fixed.contains(&"c".repeat(60)));
+		assert!(lint(&fixed, &options, Platform::Windows).is_empty());
+	}
+
+	#[test]
+	fn test_split_line_with_long_comment() {
+		let comment = "c".repeat(300);
+		let content =
+			format!("::1 localhost\n127.0.0.1 a.test # {}\n", comment);
+		let options = HostsOptions::default();
+		let lints = lint(&content, &options, Platform::Windows);
+		assert_eq!(lints[0].rule, LintRule::LineTooLong(319));
+
+		let fixed = apply_fix(&content, "\n", &lints[0]);
+		assert_eq!(
+			fixed,
+			format!("::1 localhost\n# {}\n127.0.0.1 a.test\n", comment)
+		);
+		assert!(lint(&fixed, &options, Platform::Windows).is_empty());
+	}
+
+	#[rstest]
+	#[case("#(hed) 127.0.0.1\ta.test\n", Separator::Tab)]
+	#[case("127.0.0.1\ta.test # note\n", Separator::Tab)]
+	#[case("127.0.0.1 a.test\t# note\n", Separator::Space)]
+	fn test_mixed_separators_outside_fields(
+		#[case] line: &str,
+		#[case] separator: Separator,
+	) {
+		let mut options = HostsOptions::default();
+		options.format.separator = separator;
+		let content = format!("::1 localhost\n{}", line);
+
+		assert!(lint(&content, &options, Platform::Linux).is_empty());
+	}
+
+	#[test]
+	fn test_long_disabled_line() {
+		let content =
+			format!("::1 localhost\n#(hed) 127.0.0.1 {}\n", "a".repeat(300));
+
+		assert!(rules(&content, Platform::Windows).is_empty());
+	}
+}
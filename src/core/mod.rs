@@ -1,9 +1,44 @@
+mod audit;
+mod autosave;
+mod changes;
 mod channel;
+mod command;
+mod diff;
+mod file_format;
+mod file_tab;
+mod find_replace;
+mod fonts;
 mod hed;
+mod host_timer;
+mod hosts_error;
 mod hosts_info;
+mod hosts_options;
 mod item;
 mod item_form;
+mod keymap;
+mod lint;
+mod normalize;
+mod path_form;
+mod protected;
+mod selection;
+mod settings;
+mod toast;
 
+pub use audit::{Finding, RuleKind, Severity};
+pub use changes::{Changes, HostChange, RemovedHost};
 pub use channel::Event;
-pub use hed::{Hed, OpenedWindow, ViewKind};
+pub use command::Command;
+pub use diff::{DiffLine, DiffTag};
+pub use find_replace::ReplaceTarget;
+pub use fonts::LoadedFonts;
+pub use hed::{DiscardAction, Hed, OpenedWindow, ViewKind};
+pub use host_timer::{HostTimer, TimerTarget};
+pub use hosts_error::HostsError;
 use hosts_info::HostsInfo;
+pub use hosts_options::{DisabledMark, HostsOptions, Separator};
+pub use item::{Host, Item};
+pub use keymap::Keymap;
+pub use lint::{Lint, LintLevel, Platform};
+pub use selection::{HostKey, SelectMode};
+pub use settings::{Settings, Theme};
+pub use toast::{Toast, ToastAction, ToastLevel};
@@ -0,0 +1,264 @@
+use std::{
+	collections::{HashMap, HashSet},
+	net::IpAddr,
+};
+
+use super::hosts_info::{reorder_lines_by, Line, ValidLine};
+
+/// Which steps of the normalize command to run, in the order of the fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizeSteps {
+	pub lowercase: bool,
+	/// Merge all lines of an ip and state into the first one of them.
+	pub merge_ips: bool,
+	/// Remove hosts listed again for the same ip.
+	pub dedupe: bool,
+	/// Sort the hosts of each line, and the lines by ip.
+	pub sort: bool,
+	/// Move comments that are not above a hosts line to the end of the file,
+	/// the header before the first hosts line stays.
+	pub move_comments: bool,
+	pub collapse_blank: bool,
+}
+
+impl Default for NormalizeSteps {
+	fn default() -> Self {
+		Self {
+			lowercase: true,
+			merge_ips: true,
+			dedupe: true,
+			sort: false,
+			move_comments: false,
+			collapse_blank: true,
+		}
+	}
+}
+
+pub(super) fn normalize_lines(
+	mut lines: Vec<Line>,
+	steps: &NormalizeSteps,
+) -> Vec<Line> {
+	if steps.lowercase {
+		lowercase(&mut lines);
+	}
+	if steps.merge_ips {
+		lines = merge_ips(lines);
+	}
+	if steps.dedupe {
+		lines = dedupe(lines);
+	}
+	if steps.sort {
+		lines = sort(lines);
+	}
+	if steps.move_comments {
+		lines = move_comments(lines);
+	}
+	if steps.collapse_blank {
+		lines = collapse_blank(lines);
+	}
+
+	if lines
+		.last()
+		.is_some_and(|line| !matches!(line, Line::Empty))
+	{
+		lines.push(Line::Empty);
+	}
+	lines
+}
+
+fn lowercase(lines: &mut [Line]) {
+	for line in lines {
+		match line {
+			Line::Valid(valid_line) => {
+				for host in &mut valid_line.hosts {
+					*host = host.to_lowercase();
+				}
+			}
+			// keep the timers matching their hosts
+			Line::Timer(timer) => {
+				timer.host = timer.host.to_lowercase();
+			}
+			_ => {}
+		}
+	}
+}
+
+fn dedupe(lines: Vec<Line>) -> Vec<Line> {
+	let mut seen: HashSet<(String, String)> = HashSet::new();
+
+	lines
+		.into_iter()
+		.filter_map(|line| {
+			let Line::Valid(mut valid_line) = line else {
+				return Some(line);
+			};
+			valid_line.hosts.retain(|host| {
+				seen.insert((valid_line.ip.clone(), host.clone()))
+			});
+			(!valid_line.hosts.is_empty()).then_some(Line::Valid(valid_line))
+		})
+		.collect()
+}
+
+fn merge_ips(lines: Vec<Line>) -> Vec<Line> {
+	// the index in `new` of the first line of each ip and state
+	let mut first_lines: HashMap<(String, bool), usize> = HashMap::new();
+	let mut new: Vec<Line> = Vec::with_capacity(lines.len());
+
+	for line in lines {
+		let Line::Valid(valid_line) = line else {
+			new.push(line);
+			continue;
+		};
+		let key = (valid_line.ip.clone(), valid_line.enabled);
+		let Some(&idx) = first_lines.get(&key) else {
+			first_lines.insert(key, new.len());
+			new.push(Line::Valid(valid_line));
+			continue;
+		};
+		if let Line::Valid(first) = &mut new[idx] {
+			merge_into(first, valid_line);
+		}
+	}
+
+	new
+}
+
+fn merge_into(first: &mut ValidLine, other: ValidLine) {
+	for host in other.hosts {
+		if !first.hosts.contains(&host) {
+			first.hosts.push(host);
+		}
+	}
+	first.comment = match (first.comment.take(), other.comment) {
+		(Some(a), Some(b)) => Some(format!("{}; {}", a, b)),
+		(a, b) => a.or(b),
+	};
+}
+
+fn sort(mut lines: Vec<Line>) -> Vec<Line> {
+	let mut ips: Vec<&str> = vec![];
+	for line in &mut lines {
+		if let Line::Valid(valid_line) = line {
+			valid_line.hosts.sort();
+		}
+	}
+	for line in &lines {
+		if let Line::Valid(valid_line) = line {
+			ips.push(&valid_line.ip);
+		}
+	}
+	// ips that are not addresses, like ones with a zone, go last
+	ips.sort_by_key(|ip| {
+		let addr = ip.parse::<IpAddr>().ok();
+		(addr.is_none(), addr, *ip)
+	});
+	ips.dedup();
+
+	let ip_order: HashMap<&str, usize> =
+		ips.into_iter().enumerate().map(|(i, ip)| (ip, i)).collect();
+	reorder_lines_by(&lines, &ip_order)
+}
+
+fn move_comments(lines: Vec<Line>) -> Vec<Line> {
+	let Some(header_len) =
+		lines.iter().position(|line| matches!(line, Line::Valid(_)))
+	else {
+		return lines;
+	};
+
+	let mut new = Vec::with_capacity(lines.len());
+	let mut orphans = vec![];
+	let mut comments = vec![];
+	let mut lines = lines.into_iter();
+	new.extend(lines.by_ref().take(header_len));
+
+	for line in lines {
+		match line {
+			Line::Comment(_) => {
+				comments.push(line);
+			}
+			Line::Empty => {
+				orphans.append(&mut comments);
+				new.push(line);
+			}
+			_ => {
+				new.append(&mut comments);
+				new.push(line);
+			}
+		}
+	}
+	orphans.append(&mut comments);
+
+	if !orphans.is_empty() {
+		if new.last().is_some_and(|line| !matches!(line, Line::Empty)) {
+			new.push(Line::Empty);
+		}
+		new.append(&mut orphans);
+	}
+	new
+}
+
+fn collapse_blank(lines: Vec<Line>) -> Vec<Line> {
+	let mut new: Vec<Line> = Vec::with_capacity(lines.len());
+
+	for line in lines {
+		let is_empty = matches!(line, Line::Empty);
+		let previous_empty =
+			new.last().is_none_or(|last| matches!(last, Line::Empty));
+		if !(is_empty && previous_empty) {
+			new.push(line);
+		}
+	}
+	while new.last().is_some_and(|line| matches!(line, Line::Empty)) {
+		new.pop();
+	}
+
+	new
+}
+
+#[cfg(test)]
+mod tests {
+	use insta::assert_snapshot;
+	use rstest::rstest;
+
+	use super::NormalizeSteps;
+	use crate::core::HostsInfo;
+
+	const CONTENT: &str = "# header\n\n\n\
+		127.0.0.1 B.test a.test\n\
+		# note\n\
+		10.0.0.1 c.test # first\n\n\
+		# orphan\n\n\n\
+		127.0.0.1 a.test d.test\n\
+		#(hed) 10.0.0.1 e.test\n\
+		10.0.0.1 C.test # second\n\n";
+
+	fn normalize(steps: NormalizeSteps) -> String {
+		let mut hosts_info = HostsInfo::default();
+		hosts_info.content = CONTENT.to_string();
+		hosts_info.file_format.trailing_newline = true;
+		hosts_info.update_list();
+
+		hosts_info.normalized(&steps)
+	}
+
+	#[rstest]
+	#[case("normalize_default", NormalizeSteps::default())]
+	#[case("normalize_all", NormalizeSteps {
+		sort: true,
+		move_comments: true,
+		..Default::default()
+	})]
+	#[case("normalize_none", NormalizeSteps {
+		lowercase: false,
+		merge_ips: false,
+		dedupe: false,
+		sort: false,
+		move_comments: false,
+		collapse_blank: false,
+	})]
+	fn test_normalize(#[case] name: &str, #[case] steps: NormalizeSteps) {
+		assert_snapshot!(name, normalize(steps));
+	}
+}
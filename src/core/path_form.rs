@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use crate::{i18n::t, util::StringExt};
+
+#[derive(Default)]
+pub struct PathForm {
+	pub path: String,
+	pub path_error: String,
+}
+
+impl PathForm {
+	pub fn reset(&mut self) {
+		self.path.clear();
+		self.path_error.clear();
+	}
+
+	pub fn validate(&mut self) -> Option<PathBuf> {
+		self.path.make_trim();
+		if self.path.is_empty() {
+			self.path_error = t("error.path_empty").to_string();
+			return None;
+		}
+		Some(PathBuf::from(&self.path))
+	}
+}
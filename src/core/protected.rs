@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use crate::util::get_hostname;
+
+/// Hosts a machine needs to work, always protected.
+const DEFAULT_PROTECTED_HOSTS: [&str; 3] =
+	["localhost", "broadcasthost", "ip6-localhost"];
+
+/// Hosts that are only disabled, deleted or renamed once confirmed. Host
+/// names are matched case-insensitively.
+#[derive(Debug, Clone)]
+pub struct ProtectedHosts {
+	names: HashSet<String>,
+}
+
+impl Default for ProtectedHosts {
+	fn default() -> Self {
+		Self::new(None, "")
+	}
+}
+
+impl ProtectedHosts {
+	/// The defaults, the hostname of the machine and the whitespace
+	/// separated `user_hosts`.
+	pub fn new(hostname: Option<&str>, user_hosts: &str) -> Self {
+		let names = DEFAULT_PROTECTED_HOSTS
+			.into_iter()
+			.map(String::from)
+			.chain(hostname.map(String::from))
+			.chain(user_hosts.split_whitespace().map(String::from))
+			.map(|name| name.to_lowercase())
+			.collect();
+
+		Self { names }
+	}
+
+	pub fn with_system_hostname(user_hosts: &str) -> Self {
+		Self::new(get_hostname().as_deref(), user_hosts)
+	}
+
+	pub fn contains(&self, name: &str) -> bool {
+		self.names.contains(&name.to_lowercase())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rstest::rstest;
+
+	use super::ProtectedHosts;
+
+	#[rstest]
+	#[case("localhost", true)]
+	#[case("LocalHost", true)]
+	#[case("ip6-localhost", true)]
+	#[case("my-pc", true)]
+	#[case("nas.lan", true)]
+	#[case("a.test", false)]
+	fn test_protected_hosts(#[case] name: &str, #[case] expected: bool) {
+		let protected = ProtectedHosts::new(Some("my-pc"), " nas.lan\n");
+
+		assert_eq!(protected.contains(name), expected);
+	}
+}
@@ -0,0 +1,118 @@
+use std::collections::HashSet;
+
+pub type HostKey = (usize, usize);
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SelectMode {
+	Toggle,
+	Range,
+}
+
+#[derive(Default)]
+pub struct HostSelection {
+	keys: HashSet<HostKey>,
+	anchor: Option<HostKey>,
+}
+
+impl HostSelection {
+	pub fn contains(&self, key: HostKey) -> bool {
+		self.keys.contains(&key)
+	}
+
+	pub fn len(&self) -> usize {
+		self.keys.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+
+	pub fn clear(&mut self) {
+		self.keys.clear();
+		self.anchor = None;
+	}
+
+	pub fn toggle(&mut self, key: HostKey) {
+		if !self.keys.remove(&key) {
+			self.keys.insert(key);
+		}
+		self.anchor = Some(key);
+	}
+
+	/// Select every key between the anchor and `key`, using `order` as the
+	/// visual order of hosts.
+	pub fn select_range(&mut self, order: &[HostKey], key: HostKey) {
+		let Some(end) = order.iter().position(|k| *k == key) else {
+			return;
+		};
+		let start = self
+			.anchor
+			.and_then(|anchor| order.iter().position(|k| *k == anchor))
+			.unwrap_or(end);
+		let (from, to) = if start <= end {
+			(start, end)
+		} else {
+			(end, start)
+		};
+		self.keys.extend(&order[from..=to]);
+		self.anchor.get_or_insert(key);
+	}
+
+	/// Drop the keys of hosts that no longer exist.
+	pub fn retain_existing(&mut self, existing: &[HostKey]) {
+		let existing: HashSet<&HostKey> = existing.iter().collect();
+		self.keys.retain(|key| existing.contains(key));
+		self.anchor = self.anchor.filter(|anchor| existing.contains(anchor));
+	}
+
+	pub fn to_vec(&self, order: &[HostKey]) -> Vec<HostKey> {
+		order
+			.iter()
+			.filter(|key| self.keys.contains(key))
+			.copied()
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{HostKey, HostSelection};
+
+	// two items of two hosts, in visual order
+	const ORDER: [HostKey; 4] = [(0, 0), (0, 1), (1, 2), (1, 3)];
+
+	#[test]
+	fn test_select_range_across_items() {
+		let mut selection = HostSelection::default();
+		selection.toggle((0, 1));
+		selection.select_range(&ORDER, (1, 3));
+
+		assert_eq!(selection.to_vec(&ORDER), [(0, 1), (1, 2), (1, 3)]);
+
+		selection.clear();
+		selection.toggle((1, 2));
+		selection.select_range(&ORDER, (0, 0));
+
+		assert_eq!(selection.to_vec(&ORDER), [(0, 0), (0, 1), (1, 2)]);
+	}
+
+	#[test]
+	fn test_select_range_without_anchor() {
+		let mut selection = HostSelection::default();
+		selection.select_range(&ORDER, (1, 2));
+
+		assert_eq!(selection.to_vec(&ORDER), [(1, 2)]);
+	}
+
+	#[test]
+	fn test_retain_existing() {
+		let mut selection = HostSelection::default();
+		selection.toggle((0, 0));
+		selection.toggle((1, 3));
+		selection.retain_existing(&ORDER[..2]);
+
+		assert_eq!(selection.to_vec(&ORDER), [(0, 0)]);
+		selection.select_range(&ORDER, (0, 1));
+		assert_eq!(selection.len(), 2);
+	}
+}
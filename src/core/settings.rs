@@ -0,0 +1,383 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+use toml::{Table, Value};
+
+use super::{
+	command::Command,
+	hed::ViewKind,
+	hosts_options::{DisabledMark, FormatOptions, HostsOptions, Separator},
+	keymap::{parse_shortcut, shortcut_to_string, Keymap},
+	lint::Platform,
+};
+use crate::{i18n::Lang, util::get_config_dir};
+
+const SETTINGS_FILE_NAME: &str = "settings.toml";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+	/// UI language, `None` follows the system locale.
+	pub language: Option<Lang>,
+	pub theme: Theme,
+	pub view_kind: ViewKind,
+	pub window_size: [f32; 2],
+	pub font_size: f32,
+	/// Font file used before the bundled and system fonts, empty means none.
+	pub font_path: String,
+	/// Hosts file to edit, empty means the system hosts file.
+	pub hosts_path: String,
+	/// Whitespace separated hosts protected besides the defaults.
+	pub protected_hosts: String,
+	/// Platform the hosts file is linted for, `None` follows the system.
+	pub lint_platform: Option<Platform>,
+	pub hosts_options: HostsOptions,
+	pub keymap: Keymap,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Theme {
+	Light,
+	#[default]
+	Dark,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self {
+			language: None,
+			theme: Theme::default(),
+			view_kind: ViewKind::default(),
+			window_size: [1000.0, 700.0],
+			font_size: 16.0,
+			font_path: String::new(),
+			hosts_path: String::new(),
+			protected_hosts: String::new(),
+			lint_platform: None,
+			hosts_options: HostsOptions::default(),
+			keymap: Keymap::default(),
+		}
+	}
+}
+
+impl Settings {
+	pub fn load() -> Self {
+		settings_path()
+			.and_then(|path| Ok(fs::read_to_string(path)?))
+			.and_then(|content| Ok(content.parse::<Table>()?))
+			.map(|table| Self::from_table(&table))
+			.unwrap_or_default()
+	}
+
+	pub fn save(&self) -> Result<()> {
+		let path = settings_path()?;
+		if let Some(dir) = path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		fs::write(path, self.to_table().to_string())?;
+
+		Ok(())
+	}
+
+	fn from_table(table: &Table) -> Self {
+		let default = Self::default();
+		let hosts = sub_table(table, "hosts");
+		let format = sub_table(table, "format");
+		let default_format = FormatOptions::default();
+
+		let mut keymap = Keymap::default();
+		for (key, value) in sub_table(table, "keymap") {
+			let Some(command) =
+				Command::BINDABLE.into_iter().find(|c| c.id() == key)
+			else {
+				continue;
+			};
+			if let Some(s) = value.as_str() {
+				keymap.set(&command, parse_shortcut(s));
+			}
+		}
+
+		let window_size = table
+			.get("window_size")
+			.and_then(Value::as_array)
+			.and_then(|size| {
+				Some([as_f32(size.first()?)?, as_f32(size.get(1)?)?])
+			})
+			.unwrap_or(default.window_size);
+
+		Self {
+			language: get_str(table, "language").and_then(Lang::from_code),
+			theme: match get_str(table, "theme") {
+				Some("light") => Theme::Light,
+				Some("dark") => Theme::Dark,
+				_ => default.theme,
+			},
+			view_kind: match get_str(table, "view_kind") {
+				Some("options") => ViewKind::Options,
+				Some("text") => ViewKind::Text,
+				_ => default.view_kind,
+			},
+			window_size,
+			font_size: table
+				.get("font_size")
+				.and_then(as_f32)
+				.unwrap_or(default.font_size),
+			font_path: get_str(table, "font_path")
+				.map(String::from)
+				.unwrap_or(default.font_path),
+			hosts_path: get_str(table, "hosts_path")
+				.map(String::from)
+				.unwrap_or(default.hosts_path),
+			protected_hosts: hosts
+				.get("protected")
+				.and_then(Value::as_array)
+				.map(|hosts| {
+					let hosts: Vec<&str> =
+						hosts.iter().filter_map(Value::as_str).collect();
+					hosts.join(" ")
+				})
+				.unwrap_or(default.protected_hosts),
+			lint_platform: get_str(table, "lint_platform")
+				.and_then(Platform::from_id),
+			hosts_options: HostsOptions {
+				parse_commented: get_bool(&hosts, "parse_commented")
+					.unwrap_or_default(),
+				disabled_mark: match get_str(&hosts, "disabled_mark") {
+					Some("plain") => DisabledMark::Plain,
+					_ => DisabledMark::Hed,
+				},
+				format: FormatOptions {
+					align_ip: get_bool(&format, "align_ip")
+						.unwrap_or(default_format.align_ip),
+					max_hosts_per_line: get_usize(
+						&format,
+						"max_hosts_per_line",
+					)
+					.unwrap_or(default_format.max_hosts_per_line),
+					max_line_length: get_usize(&format, "max_line_length")
+						.unwrap_or(default_format.max_line_length),
+					one_host_per_line: get_bool(&format, "one_host_per_line")
+						.unwrap_or(default_format.one_host_per_line),
+					separator: match get_str(&format, "separator") {
+						Some("tab") => Separator::Tab,
+						_ => Separator::Space,
+					},
+				},
+			},
+			keymap,
+		}
+	}
+
+	fn to_table(&self) -> Table {
+		let mut table = Table::new();
+		let mut hosts = Table::new();
+		let mut format = Table::new();
+		let hosts_options = &self.hosts_options;
+		let format_options = &hosts_options.format;
+
+		table.insert(
+			"language".into(),
+			self.language.map_or("auto", |lang| lang.code()).into(),
+		);
+		table.insert(
+			"theme".into(),
+			match self.theme {
+				Theme::Light => "light",
+				Theme::Dark => "dark",
+			}
+			.into(),
+		);
+		table.insert(
+			"view_kind".into(),
+			match self.view_kind {
+				ViewKind::Options => "options",
+				ViewKind::Text => "text",
+			}
+			.into(),
+		);
+		table.insert(
+			"window_size".into(),
+			Value::Array(
+				self.window_size
+					.iter()
+					.map(|v| Value::Float(*v as f64))
+					.collect(),
+			),
+		);
+		table.insert("font_size".into(), Value::Float(self.font_size as f64));
+		table.insert("font_path".into(), self.font_path.clone().into());
+		table.insert("hosts_path".into(), self.hosts_path.clone().into());
+		table.insert(
+			"lint_platform".into(),
+			self.lint_platform.map_or("auto", |p| p.id()).into(),
+		);
+
+		hosts.insert(
+			"parse_commented".into(),
+			hosts_options.parse_commented.into(),
+		);
+		hosts.insert(
+			"disabled_mark".into(),
+			match hosts_options.disabled_mark {
+				DisabledMark::Hed => "hed",
+				DisabledMark::Plain => "plain",
+			}
+			.into(),
+		);
+		hosts.insert(
+			"protected".into(),
+			Value::Array(
+				self.protected_hosts
+					.split_whitespace()
+					.map(Value::from)
+					.collect(),
+			),
+		);
+
+		format.insert("align_ip".into(), format_options.align_ip.into());
+		format.insert(
+			"max_hosts_per_line".into(),
+			(format_options.max_hosts_per_line as i64).into(),
+		);
+		format.insert(
+			"max_line_length".into(),
+			(format_options.max_line_length as i64).into(),
+		);
+		format.insert(
+			"one_host_per_line".into(),
+			format_options.one_host_per_line.into(),
+		);
+		format.insert(
+			"separator".into(),
+			match format_options.separator {
+				Separator::Space => "space",
+				Separator::Tab => "tab",
+			}
+			.into(),
+		);
+
+		let keymap: Table = self
+			.keymap
+			.iter()
+			.map(|(command, shortcut)| {
+				let s = shortcut.map(|s| shortcut_to_string(&s));
+				(command.id().to_string(), s.unwrap_or_default().into())
+			})
+			.collect();
+
+		table.insert("hosts".into(), Value::Table(hosts));
+		table.insert("format".into(), Value::Table(format));
+		table.insert("keymap".into(), Value::Table(keymap));
+
+		table
+	}
+
+	pub fn lang(&self) -> Lang {
+		self.language.unwrap_or_else(Lang::detect)
+	}
+
+	pub fn lint_platform(&self) -> Platform {
+		self.lint_platform.unwrap_or_else(Platform::current)
+	}
+
+	/// The font file set by the user, if any.
+	pub fn custom_font_path(&self) -> Option<PathBuf> {
+		non_empty_path(&self.font_path)
+	}
+
+	/// The hosts file set by the user, if any.
+	pub fn custom_hosts_path(&self) -> Option<PathBuf> {
+		non_empty_path(&self.hosts_path)
+	}
+}
+
+fn non_empty_path(path: &str) -> Option<PathBuf> {
+	let path = path.trim();
+	if path.is_empty() {
+		None
+	} else {
+		Some(PathBuf::from(path))
+	}
+}
+
+fn settings_path() -> Result<PathBuf> {
+	Ok(get_config_dir()?.join(SETTINGS_FILE_NAME))
+}
+
+fn sub_table(table: &Table, key: &str) -> Table {
+	table
+		.get(key)
+		.and_then(Value::as_table)
+		.cloned()
+		.unwrap_or_default()
+}
+
+fn get_str<'a>(table: &'a Table, key: &str) -> Option<&'a str> {
+	table.get(key).and_then(Value::as_str)
+}
+
+fn get_bool(table: &Table, key: &str) -> Option<bool> {
+	table.get(key).and_then(Value::as_bool)
+}
+
+fn get_usize(table: &Table, key: &str) -> Option<usize> {
+	table
+		.get(key)
+		.and_then(Value::as_integer)
+		.and_then(|v| usize::try_from(v).ok())
+}
+
+fn as_f32(value: &Value) -> Option<f32> {
+	value
+		.as_float()
+		.or_else(|| value.as_integer().map(|v| v as f64))
+		.map(|v| v as f32)
+}
+
+#[cfg(test)]
+mod tests {
+	use insta::assert_snapshot;
+	use toml::Table;
+
+	use super::{Settings, Theme};
+	use crate::{
+		core::{command::Command, hosts_options::Separator, lint::Platform},
+		i18n::Lang,
+	};
+
+	#[test]
+	fn test_settings_round_trip() {
+		let mut settings = Settings {
+			language: Some(Lang::ZhCn),
+			theme: Theme::Light,
+			font_size: 18.0,
+			font_path: "/tmp/font.ttf".to_string(),
+			hosts_path: "/tmp/hosts".to_string(),
+			protected_hosts: "nas.lan router.lan".to_string(),
+			lint_platform: Some(Platform::MacOs),
+			..Default::default()
+		};
+		settings.hosts_options.parse_commented = true;
+		settings.hosts_options.format.separator = Separator::Tab;
+		settings.hosts_options.format.max_hosts_per_line = 9;
+		settings.keymap.set(&Command::Reload, None);
+
+		let content = settings.to_table().to_string();
+		let table = content.parse::<Table>().unwrap();
+
+		assert_eq!(Settings::from_table(&table), settings);
+		assert_snapshot!("settings_to_table", content);
+	}
+
+	#[test]
+	fn test_settings_partial() {
+		let table = "font_size = 20\n[format]\nalign_ip = true\n"
+			.parse::<Table>()
+			.unwrap();
+		let settings = Settings::from_table(&table);
+
+		assert_eq!(settings.font_size, 20.0);
+		assert!(settings.hosts_options.format.align_ip);
+		assert_eq!(settings.theme, Theme::default());
+		assert_eq!(settings.language, None);
+	}
+}
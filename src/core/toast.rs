@@ -0,0 +1,197 @@
+use std::{
+	collections::VecDeque,
+	path::PathBuf,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+use super::HostsInfo;
+use crate::util::now_secs;
+
+/// How long a toast stays before it is dismissed, errors stay longer.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+const ERROR_TOAST_DURATION: Duration = Duration::from_secs(10);
+
+/// Toasts kept in the history once dismissed.
+const HISTORY_LEN: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+	Success,
+	Info,
+	Warning,
+	Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastAction {
+	Undo,
+	ShowDiff,
+	ShowAudit,
+}
+
+/// A save the hosts file can be taken back from.
+#[derive(Debug)]
+pub struct SavedChange {
+	pub path: PathBuf,
+	pub before: HostsInfo,
+	pub after: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+	pub id: usize,
+	pub level: ToastLevel,
+	pub message: String,
+	pub actions: Vec<ToastAction>,
+	pub change: Option<Arc<SavedChange>>,
+	/// Unix seconds, for the history.
+	pub created_at: u64,
+	shown_at: Instant,
+}
+
+#[derive(Default)]
+pub struct Toasts {
+	next_id: usize,
+	pub active: Vec<Toast>,
+	pub history: VecDeque<Toast>,
+	pub history_open: bool,
+}
+
+impl Toast {
+	fn duration(&self) -> Duration {
+		match self.level {
+			ToastLevel::Error => ERROR_TOAST_DURATION,
+			_ => TOAST_DURATION,
+		}
+	}
+}
+
+impl Toasts {
+	pub fn push(&mut self, level: ToastLevel, message: String) -> usize {
+		self.push_change(level, message, None)
+	}
+
+	/// A toast for a save, with the actions that need the saved change.
+	pub fn push_change(
+		&mut self,
+		level: ToastLevel,
+		message: String,
+		change: Option<SavedChange>,
+	) -> usize {
+		let id = self.next_id;
+		self.next_id += 1;
+		let actions = if change.is_some() {
+			vec![ToastAction::Undo, ToastAction::ShowDiff]
+		} else {
+			vec![]
+		};
+		self.active.push(Toast {
+			id,
+			level,
+			message,
+			actions,
+			change: change.map(Arc::new),
+			created_at: now_secs(),
+			shown_at: Instant::now(),
+		});
+		id
+	}
+
+	/// A toast with an action that needs no saved change.
+	pub fn push_action(
+		&mut self,
+		level: ToastLevel,
+		message: String,
+		action: ToastAction,
+	) -> usize {
+		let id = self.push(level, message);
+		if let Some(toast) = self.active.last_mut() {
+			toast.actions.push(action);
+		}
+		id
+	}
+
+	pub fn get(&self, id: usize) -> Option<&Toast> {
+		self.active
+			.iter()
+			.chain(self.history.iter())
+			.find(|toast| toast.id == id)
+	}
+
+	pub fn dismiss(&mut self, id: usize) {
+		if let Some(idx) = self.active.iter().position(|toast| toast.id == id) {
+			let toast = self.active.remove(idx);
+			self.add_history(toast);
+		}
+	}
+
+	/// Keep `id` on screen while the pointer is over it.
+	pub fn hold(&mut self, id: usize) {
+		if let Some(toast) = self.active.iter_mut().find(|toast| toast.id == id)
+		{
+			toast.shown_at = Instant::now();
+		}
+	}
+
+	/// Dismiss the toasts whose time is up, returns when to check again.
+	pub fn tick(&mut self) -> Option<Duration> {
+		let (expired, active): (Vec<Toast>, Vec<Toast>) =
+			std::mem::take(&mut self.active)
+				.into_iter()
+				.partition(|toast| {
+					toast.shown_at.elapsed() >= toast.duration()
+				});
+		self.active = active;
+		for toast in expired {
+			self.add_history(toast);
+		}
+
+		self.active
+			.iter()
+			.map(|toast| {
+				toast.duration().saturating_sub(toast.shown_at.elapsed())
+			})
+			.min()
+	}
+
+	fn add_history(&mut self, toast: Toast) {
+		self.history.push_front(toast);
+		self.history.truncate(HISTORY_LEN);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::{Duration, Instant};
+
+	use super::{ToastLevel, Toasts, HISTORY_LEN, TOAST_DURATION};
+
+	#[test]
+	fn test_toasts_expire_into_history() {
+		let mut toasts = Toasts::default();
+		let id = toasts.push(ToastLevel::Success, "saved".to_string());
+		toasts.push(ToastLevel::Error, "failed".to_string());
+
+		assert!(toasts.tick().is_some());
+		toasts.active[0].shown_at = Instant::now() - TOAST_DURATION;
+		assert!(toasts.tick().unwrap() > Duration::from_secs(5));
+
+		assert_eq!(toasts.active.len(), 1);
+		assert_eq!(toasts.history[0].id, id);
+		assert!(toasts.get(id).is_some());
+	}
+
+	#[test]
+	fn test_history_len() {
+		let mut toasts = Toasts::default();
+		for i in 0..HISTORY_LEN + 5 {
+			let id = toasts.push(ToastLevel::Info, i.to_string());
+			toasts.dismiss(id);
+		}
+
+		assert!(toasts.active.is_empty());
+		assert_eq!(toasts.history.len(), HISTORY_LEN);
+		assert_eq!(toasts.history[0].message, (HISTORY_LEN + 4).to_string());
+	}
+}
@@ -0,0 +1,298 @@
+pub const MESSAGES: &[(&str, &str)] = &[
+	// editor header
+	("view.options", "Options View"),
+	("view.text", "Text View"),
+	("menu.file", "File"),
+	("tab.system", "System hosts"),
+	("header.read_only", "Read-only"),
+	// options view
+	("options.search", "Search ip, hosts"),
+	("options.add_hosts", "Add hosts"),
+	("options.delete", "Delete"),
+	("options.enable_all", "Enable all"),
+	("options.disable_all", "Disable all"),
+	("options.edit", "Edit"),
+	("options.selected", "{0} selected"),
+	("options.enable", "Enable"),
+	("options.disable", "Disable"),
+	("options.move_to", "Move to…"),
+	("options.copy_to", "Copy to…"),
+	("options.clear", "Clear"),
+	("options.replace", "Replace"),
+	("options.temporary", "Temporary…"),
+	("options.cancel_timer", "Cancel timer"),
+	("options.revert", "Revert this change"),
+	("change.added", "Added since the last save"),
+	("change.removed", "Removed since the last save"),
+	("change.renamed", "Renamed from {0} since the last save"),
+	("change.toggled", "Toggled since the last save"),
+	("options.protected", "Protected, changes need confirming"),
+	("timer.enabled", "enabled"),
+	("timer.disabled", "disabled"),
+	("timer.tooltip", "Back to {0} at {1}"),
+	// text view
+	("text.format", "Format"),
+	("text.normalize", "Normalize…"),
+	// windows
+	("window.ok", "OK"),
+	("window.new_item", "New item"),
+	("window.add_hosts", "Add Hosts"),
+	("window.edit_host", "Edit Host"),
+	("window.find_replace", "Find and Replace"),
+	("window.move_hosts", "Move Hosts"),
+	("window.copy_hosts", "Copy Hosts"),
+	("window.settings", "Settings"),
+	("window.timer", "Temporary"),
+	("window.open_file", "Open File"),
+	("window.save_as", "Save As"),
+	("window.diff", "Changes"),
+	("window.restore_draft", "Restore Unsaved Draft"),
+	(
+		"restore.message",
+		"Unsaved changes to {0} were kept from {1}, restore them?",
+	),
+	("restore.restore", "Restore"),
+	("restore.discard", "Discard"),
+	("window.unsaved", "Unsaved Changes"),
+	(
+		"confirm.close",
+		"Save the changes to the hosts file before closing?",
+	),
+	(
+		"confirm.close_tab",
+		"Save the changes to this file before closing it?",
+	),
+	(
+		"confirm.reload",
+		"Save the changes to the hosts file before reloading?",
+	),
+	(
+		"confirm.hosts_path",
+		"Save the changes to the hosts file before opening another one?",
+	),
+	("confirm.save", "Save"),
+	("confirm.discard", "Discard"),
+	("confirm.cancel", "Cancel"),
+	("window.protected", "Protected Hosts"),
+	(
+		"confirm.protected",
+		"Protected: {0}. Changing them can break this machine's network.",
+	),
+	("confirm.change_anyway", "Change anyway"),
+	("window.audit", "Security Audit"),
+	("window.import_audit_rules", "Import Audit Rules"),
+	("audit.bundled_rules", "Bundled rules"),
+	("audit.imported_rules", "Imported rules"),
+	("audit.rules_version", "{0}, version {1}"),
+	("audit.import_rules", "Import rules…"),
+	("audit.no_findings", "No suspicious entries found"),
+	("audit.findings", "{0} suspicious entries"),
+	("audit.disable_all", "Disable all"),
+	("audit.redirect", "Redirected to a public ip"),
+	("audit.blocked", "Blocked"),
+	("window.normalize", "Normalize"),
+	("normalize.lowercase", "Lowercase hosts"),
+	("normalize.merge_ips", "Merge lines of an ip"),
+	("normalize.dedupe", "Remove duplicate hosts"),
+	("normalize.sort", "Sort ips and hosts"),
+	("normalize.move_comments", "Move orphan comments to the end"),
+	("normalize.collapse_blank", "Collapse blank lines"),
+	("normalize.apply", "Apply"),
+	("normalize.no_changes", "Already normalized"),
+	(
+		"lint.invalid_line",
+		"Not a comment nor an ip followed by hosts",
+	),
+	(
+		"lint.missing_ipv6_localhost",
+		"No `::1 localhost` line, IPv6 lookups of localhost may fail",
+	),
+	("lint.mixed_separators", "Mixes tabs and spaces"),
+	(
+		"lint.too_many_hosts",
+		"{0} hosts on a line, Windows ignores the ones past {1}",
+	),
+	(
+		"lint.line_too_long",
+		"{0} characters, Windows drops lines longer than {1}",
+	),
+	(
+		"lint.mdns_host",
+		"{0} is looked up through Bonjour first on macOS, which is slow",
+	),
+	("lint.fix", "Fix"),
+	("form.ip", "ip"),
+	("form.hosts", "hosts"),
+	("form.to_ip", "to ip"),
+	("form.find", "find"),
+	("form.replace", "replace"),
+	("form.in", "in"),
+	("form.text", "text"),
+	("form.regex", "regex"),
+	("form.preview", "preview"),
+	("form.no_match", "no match"),
+	("form.state", "state"),
+	("form.until", "for / until"),
+	("form.path", "path"),
+	("form.until_hint", "30m, 1h30m, 18:30 or 2024-05-01 09:00"),
+	(
+		"form.timer_note",
+		"Hosts go back to their current state when the time is up",
+	),
+	// validation
+	("error.ip_empty", "IP address is empty"),
+	("error.ip_invalid", "`{0}` is not a valid IP address"),
+	("error.hosts_empty", "hosts is empty"),
+	("error.until_invalid", "`{0}` is not a duration or time"),
+	("error.until_past", "the time is already past"),
+	(
+		"error.unknown_encoding",
+		"The hosts file is not in a supported encoding",
+	),
+	(
+		"error.unencodable",
+		"The content can not be saved in the encoding of the hosts file",
+	),
+	(
+		"error.sys_hosts_path",
+		"Failed to get the path of system hosts file",
+	),
+	("error.read_failed", "Failed to read {0}"),
+	("error.save_failed", "Failed to save {0}"),
+	("error.permission_denied", "Permission denied"),
+	("error.not_found", "The file does not exist"),
+	(
+		"error.elevation_failed",
+		"Administrator access was not granted",
+	),
+	("error.path_empty", "path is empty"),
+	// recoveries
+	("recovery.retry", "Retry"),
+	("recovery.elevate", "Retry as administrator"),
+	("recovery.read_only", "Continue read-only"),
+	("recovery.choose_file", "Choose another file…"),
+	("recovery.save_draft_as", "Save draft as…"),
+	("recovery.dismiss", "Dismiss"),
+	// toasts
+	("toast.saved", "Saved {0} changes to {1}"),
+	("toast.saved_as", "Saved the draft to {0}"),
+	("toast.reloaded", "Reloaded {0}"),
+	(
+		"toast.reloaded_discarded",
+		"Reloaded {0}, unsaved changes were discarded",
+	),
+	(
+		"toast.timers_reverted",
+		"Expired timers were reverted in the hosts file",
+	),
+	(
+		"toast.timers_unwritable",
+		"Timers in {0} are up but it can't be written, save it to revert them",
+	),
+	("toast.undo", "Undo"),
+	("toast.show_diff", "Show diff"),
+	("toast.show_audit", "Show report"),
+	(
+		"toast.audit_findings",
+		"{0} suspicious entries in {1}, they may come from malware",
+	),
+	(
+		"toast.audit_rules_invalid",
+		"Invalid audit rules, using the bundled ones: {0}",
+	),
+	(
+		"toast.audit_rules_imported",
+		"Imported audit rules version {0}",
+	),
+	(
+		"toast.settings_save_failed",
+		"Failed to save the settings: {0}",
+	),
+	("toast.font_load_failed", "Could not load the font {0}: {1}"),
+	("toast.history", "Notifications"),
+	("toast.no_history", "No notifications yet"),
+	// settings
+	("settings.language", "language"),
+	("settings.language_auto", "System"),
+	("settings.theme", "theme"),
+	("settings.light", "Light"),
+	("settings.dark", "Dark"),
+	("settings.view", "view"),
+	("settings.options", "Options"),
+	("settings.text", "Text"),
+	("settings.font_size", "font size"),
+	("settings.font_file", "font file"),
+	("settings.system_fonts", "bundled and system fonts"),
+	("settings.hosts_file", "hosts file"),
+	("settings.system_hosts_file", "system hosts file"),
+	("settings.protected_hosts", "protected hosts"),
+	(
+		"settings.protected_hint",
+		"localhost and this machine's name are always protected",
+	),
+	("settings.parse", "parse"),
+	(
+		"settings.parse_commented",
+		"Treat `# ip host` lines as disabled hosts",
+	),
+	("settings.disabled_mark", "Write disabled hosts as"),
+	(
+		"settings.disabled_mark_requires",
+		"Requires `# ip host` lines to be treated as disabled hosts",
+	),
+	("settings.format", "format"),
+	("settings.align_ip", "Align ip column"),
+	("settings.one_host_per_line", "One host per line"),
+	("settings.max_hosts_per_line", "Max hosts per line"),
+	("settings.max_line_length", "Max line length"),
+	("settings.no_limit", "0 means no limit"),
+	("settings.spaces", "Spaces"),
+	("settings.tabs", "Tabs"),
+	("settings.lint", "lint for"),
+	("settings.lint_auto", "System ({0})"),
+	("settings.shortcuts", "shortcuts"),
+	("settings.press_keys", "press keys…"),
+	("settings.unbound", "none"),
+	("settings.record_shortcut", "Click to record a shortcut"),
+	("settings.clear_shortcut", "Clear"),
+	("settings.restore_defaults", "Restore defaults"),
+	("keymap.reserved", "reserved for text editing"),
+	("keymap.conflict", "also bound to `{0}`"),
+	// commands
+	("command.save", "Save"),
+	("command.reset", "Reset"),
+	("command.reload", "Reload hosts file"),
+	("command.open_file", "Open file"),
+	("command.save_as", "Save as"),
+	("command.close_tab", "Close tab"),
+	("command.format", "Format"),
+	("command.normalize", "Normalize"),
+	("command.new_item", "New item"),
+	("command.find_replace", "Find and replace"),
+	("command.settings", "Settings"),
+	("command.command_palette", "Command palette"),
+	("command.audit", "Security audit"),
+	("command.options_view", "Switch to options view"),
+	("command.text_view", "Switch to text view"),
+	("command.toggle_host", "Toggle host"),
+	("command.jump_to_item", "Go to item"),
+	("command.go_to_item", "Go to item: {0}"),
+	("command.enable_host", "Enable host: {0} ({1})"),
+	("command.disable_host", "Disable host: {0} ({1})"),
+	("palette.placeholder", "Type a command"),
+	("palette.no_match", "No matching command"),
+	// screen readers
+	("a11y.drag_item", "Drag to move item {0}"),
+	("a11y.item_actions", "Actions for item {0}"),
+	("a11y.item_ip", "IP address of item {0}"),
+	("a11y.host_selected", "{0}, selected"),
+	("a11y.hosts_content", "Hosts file content"),
+	("a11y.timer", "Back to {0} in {1}"),
+	("a11y.shortcut", "Shortcut for {0}: {1}"),
+	("a11y.clear_shortcut", "Clear shortcut for {0}"),
+	("a11y.lints", "Problems: {0}"),
+	// header
+	("header.github", "{0} on GitHub"),
+	("header.light", "☀ Light"),
+	("header.dark", "🌙 Dark"),
+];
@@ -0,0 +1,147 @@
+mod en;
+mod zh_cn;
+
+use std::{
+	collections::HashMap,
+	fmt::Display,
+	sync::{
+		atomic::{AtomicU8, Ordering::Relaxed},
+		LazyLock,
+	},
+};
+
+use crate::util::get_os_locale;
+
+static LANG: AtomicU8 = AtomicU8::new(Lang::En as u8);
+
+static CATALOGS: LazyLock<[HashMap<&str, &str>; 2]> = LazyLock::new(|| {
+	[
+		en::MESSAGES.iter().copied().collect(),
+		zh_cn::MESSAGES.iter().copied().collect(),
+	]
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Lang {
+	En,
+	ZhCn,
+}
+
+impl Lang {
+	pub const ALL: [Lang; 2] = [Lang::En, Lang::ZhCn];
+
+	/// Name of the language in itself, shown in the language picker.
+	pub fn native_name(&self) -> &'static str {
+		match self {
+			Lang::En => "English",
+			Lang::ZhCn => "简体中文",
+		}
+	}
+
+	pub fn code(&self) -> &'static str {
+		match self {
+			Lang::En => "en",
+			Lang::ZhCn => "zh-CN",
+		}
+	}
+
+	pub fn from_code(code: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|lang| lang.code() == code)
+	}
+
+	/// Pick a language from a locale such as `zh_CN.UTF-8` or `zh-Hans-CN`.
+	pub fn from_locale(locale: &str) -> Self {
+		let locale = locale.to_ascii_lowercase().replace('_', "-");
+		if locale.starts_with("zh") {
+			Lang::ZhCn
+		} else {
+			Lang::En
+		}
+	}
+
+	pub fn detect() -> Self {
+		get_os_locale()
+			.map(|locale| Self::from_locale(&locale))
+			.unwrap_or(Lang::En)
+	}
+}
+
+pub fn set_lang(lang: Lang) {
+	LANG.store(lang as u8, Relaxed);
+}
+
+pub fn lang() -> Lang {
+	if LANG.load(Relaxed) == Lang::ZhCn as u8 {
+		Lang::ZhCn
+	} else {
+		Lang::En
+	}
+}
+
+fn lookup(lang: Lang, key: &'static str) -> &'static str {
+	let catalogs = &*CATALOGS;
+	catalogs[lang as usize]
+		.get(key)
+		.or_else(|| catalogs[Lang::En as usize].get(key))
+		.copied()
+		.unwrap_or(key)
+}
+
+/// Translate `key` into the current language.
+pub fn t(key: &'static str) -> &'static str {
+	lookup(lang(), key)
+}
+
+/// Translate `key` and replace the `{0}`, `{1}`… placeholders with `args`.
+pub fn tf(key: &'static str, args: &[&dyn Display]) -> String {
+	let mut s = t(key).to_string();
+	for (i, arg) in args.iter().enumerate() {
+		s = s.replace(&format!("{{{}}}", i), &arg.to_string());
+	}
+	s
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+
+	use super::{en, lookup, zh_cn, Lang};
+
+	fn keys(messages: &[(&'static str, &str)]) -> HashSet<&'static str> {
+		messages.iter().map(|(key, _)| *key).collect()
+	}
+
+	#[test]
+	fn test_catalogs_complete() {
+		let en_keys = keys(en::MESSAGES);
+		let zh_cn_keys = keys(zh_cn::MESSAGES);
+
+		assert_eq!(en_keys.len(), en::MESSAGES.len(), "duplicate key in en");
+		assert_eq!(en_keys, zh_cn_keys);
+	}
+
+	#[test]
+	fn test_placeholders_match() {
+		for (key, en_msg) in en::MESSAGES {
+			let zh_cn_msg = lookup(Lang::ZhCn, key);
+			for i in 0..4 {
+				let placeholder = format!("{{{}}}", i);
+				assert_eq!(
+					en_msg.contains(&placeholder),
+					zh_cn_msg.contains(&placeholder),
+					"{}",
+					key
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn test_from_locale() {
+		assert_eq!(Lang::from_locale("zh_CN.UTF-8"), Lang::ZhCn);
+		assert_eq!(Lang::from_locale("zh-Hans-CN"), Lang::ZhCn);
+		assert_eq!(Lang::from_locale("en-US"), Lang::En);
+		assert_eq!(Lang::from_locale("C"), Lang::En);
+	}
+}
@@ -0,0 +1,259 @@
+pub const MESSAGES: &[(&str, &str)] = &[
+	// editor header
+	("view.options", "选项视图"),
+	("view.text", "文本视图"),
+	("menu.file", "文件"),
+	("tab.system", "系统 hosts"),
+	("header.read_only", "只读"),
+	// options view
+	("options.search", "搜索 ip、主机名"),
+	("options.add_hosts", "添加主机名"),
+	("options.delete", "删除"),
+	("options.enable_all", "全部启用"),
+	("options.disable_all", "全部禁用"),
+	("options.edit", "编辑"),
+	("options.selected", "已选择 {0} 项"),
+	("options.enable", "启用"),
+	("options.disable", "禁用"),
+	("options.move_to", "移动到…"),
+	("options.copy_to", "复制到…"),
+	("options.clear", "清除"),
+	("options.replace", "替换"),
+	("options.temporary", "临时…"),
+	("options.cancel_timer", "取消定时"),
+	("options.revert", "撤销此更改"),
+	("change.added", "上次保存后新增"),
+	("change.removed", "上次保存后删除"),
+	("change.renamed", "上次保存后由 {0} 重命名"),
+	("change.toggled", "上次保存后切换了启用状态"),
+	("options.protected", "受保护，更改前需要确认"),
+	("timer.enabled", "启用"),
+	("timer.disabled", "禁用"),
+	("timer.tooltip", "将于 {1} 恢复为{0}"),
+	// text view
+	("text.format", "格式化"),
+	("text.normalize", "规范化…"),
+	// windows
+	("window.ok", "确定"),
+	("window.new_item", "新建条目"),
+	("window.add_hosts", "添加主机名"),
+	("window.edit_host", "编辑主机名"),
+	("window.find_replace", "查找和替换"),
+	("window.move_hosts", "移动主机名"),
+	("window.copy_hosts", "复制主机名"),
+	("window.settings", "设置"),
+	("window.timer", "临时"),
+	("window.open_file", "打开文件"),
+	("window.save_as", "另存为"),
+	("window.diff", "更改"),
+	("window.restore_draft", "恢复未保存的草稿"),
+	(
+		"restore.message",
+		"保留了 {1} 时对 {0} 未保存的更改，是否恢复？",
+	),
+	("restore.restore", "恢复"),
+	("restore.discard", "丢弃"),
+	("window.unsaved", "未保存的更改"),
+	("confirm.close", "关闭前是否保存对 hosts 文件的更改？"),
+	("confirm.close_tab", "关闭前是否保存对此文件的更改？"),
+	("confirm.reload", "重新加载前是否保存对 hosts 文件的更改？"),
+	(
+		"confirm.hosts_path",
+		"打开其他 hosts 文件前是否保存对当前文件的更改？",
+	),
+	("confirm.save", "保存"),
+	("confirm.discard", "丢弃"),
+	("confirm.cancel", "取消"),
+	("window.protected", "受保护的主机名"),
+	(
+		"confirm.protected",
+		"受保护：{0}。更改它们可能导致本机网络异常。",
+	),
+	("confirm.change_anyway", "仍然更改"),
+	("window.audit", "安全检查"),
+	("window.import_audit_rules", "导入检查规则"),
+	("audit.bundled_rules", "内置规则"),
+	("audit.imported_rules", "导入的规则"),
+	("audit.rules_version", "{0}，版本 {1}"),
+	("audit.import_rules", "导入规则…"),
+	("audit.no_findings", "未发现可疑条目"),
+	("audit.findings", "{0} 个可疑条目"),
+	("audit.disable_all", "全部禁用"),
+	("audit.redirect", "重定向到公网 ip"),
+	("audit.blocked", "已屏蔽"),
+	("window.normalize", "规范化"),
+	("normalize.lowercase", "host 转为小写"),
+	("normalize.merge_ips", "合并同一 ip 的行"),
+	("normalize.dedupe", "删除重复的 host"),
+	("normalize.sort", "排序 ip 和 host"),
+	("normalize.move_comments", "将孤立注释移到末尾"),
+	("normalize.collapse_blank", "合并连续空行"),
+	("normalize.apply", "应用"),
+	("normalize.no_changes", "已是规范格式"),
+	("lint.invalid_line", "既不是注释，也不是 ip 加 hosts"),
+	(
+		"lint.missing_ipv6_localhost",
+		"缺少 `::1 localhost` 行，localhost 的 IPv6 解析可能失败",
+	),
+	("lint.mixed_separators", "混用了制表符和空格"),
+	(
+		"lint.too_many_hosts",
+		"一行有 {0} 个 host，Windows 会忽略第 {1} 个之后的",
+	),
+	(
+		"lint.line_too_long",
+		"{0} 个字符，Windows 会丢弃超过 {1} 个字符的行",
+	),
+	(
+		"lint.mdns_host",
+		"macOS 会先通过 Bonjour 解析 {0}，速度较慢",
+	),
+	("lint.fix", "修复"),
+	("form.ip", "ip"),
+	("form.hosts", "主机名"),
+	("form.to_ip", "目标 ip"),
+	("form.find", "查找"),
+	("form.replace", "替换为"),
+	("form.in", "范围"),
+	("form.text", "文本"),
+	("form.regex", "正则"),
+	("form.preview", "预览"),
+	("form.no_match", "无匹配"),
+	("form.state", "状态"),
+	("form.until", "持续 / 截止"),
+	("form.path", "路径"),
+	("form.until_hint", "30m、1h30m、18:30 或 2024-05-01 09:00"),
+	("form.timer_note", "到时后主机将恢复为当前状态"),
+	// validation
+	("error.ip_empty", "IP 地址为空"),
+	("error.ip_invalid", "`{0}` 不是有效的 IP 地址"),
+	("error.hosts_empty", "主机名为空"),
+	("error.until_invalid", "`{0}` 不是有效的时长或时间"),
+	("error.until_past", "该时间已经过去"),
+	("error.unknown_encoding", "hosts 文件的编码不受支持"),
+	("error.unencodable", "内容无法以 hosts 文件的编码保存"),
+	("error.sys_hosts_path", "无法获取系统 hosts 文件的路径"),
+	("error.read_failed", "无法读取 {0}"),
+	("error.save_failed", "无法保存 {0}"),
+	("error.permission_denied", "权限不足"),
+	("error.not_found", "文件不存在"),
+	("error.elevation_failed", "未获得管理员权限"),
+	("error.path_empty", "路径为空"),
+	// recoveries
+	("recovery.retry", "重试"),
+	("recovery.elevate", "以管理员身份重试"),
+	("recovery.read_only", "以只读方式继续"),
+	("recovery.choose_file", "选择其他文件…"),
+	("recovery.save_draft_as", "草稿另存为…"),
+	("recovery.dismiss", "关闭"),
+	// toasts
+	("toast.saved", "已保存 {0} 处更改到 {1}"),
+	("toast.saved_as", "草稿已保存到 {0}"),
+	("toast.reloaded", "已重新加载 {0}"),
+	(
+		"toast.reloaded_discarded",
+		"已重新加载 {0}，未保存的更改已丢弃",
+	),
+	("toast.timers_reverted", "到期的定时已在 hosts 文件中恢复"),
+	(
+		"toast.timers_unwritable",
+		"{0} 中的定时已到期，但无法写入文件，保存后才会恢复",
+	),
+	("toast.undo", "撤销"),
+	("toast.show_diff", "查看差异"),
+	("toast.show_audit", "查看报告"),
+	(
+		"toast.audit_findings",
+		"{1} 中有 {0} 个可疑条目，可能来自恶意软件",
+	),
+	(
+		"toast.audit_rules_invalid",
+		"检查规则无效，已使用内置规则：{0}",
+	),
+	("toast.audit_rules_imported", "已导入检查规则，版本 {0}"),
+	("toast.settings_save_failed", "保存设置失败：{0}"),
+	("toast.font_load_failed", "无法加载字体 {0}：{1}"),
+	("toast.history", "通知"),
+	("toast.no_history", "暂无通知"),
+	// settings
+	("settings.language", "语言"),
+	("settings.language_auto", "跟随系统"),
+	("settings.theme", "主题"),
+	("settings.light", "浅色"),
+	("settings.dark", "深色"),
+	("settings.view", "视图"),
+	("settings.options", "选项"),
+	("settings.text", "文本"),
+	("settings.font_size", "字号"),
+	("settings.font_file", "字体文件"),
+	("settings.system_fonts", "内置字体和系统字体"),
+	("settings.hosts_file", "hosts 文件"),
+	("settings.system_hosts_file", "系统 hosts 文件"),
+	("settings.protected_hosts", "受保护的主机名"),
+	("settings.protected_hint", "localhost 和本机名称始终受保护"),
+	("settings.parse", "解析"),
+	(
+		"settings.parse_commented",
+		"将 `# ip 主机名` 行视为已禁用的主机名",
+	),
+	("settings.disabled_mark", "已禁用主机名的写入格式"),
+	(
+		"settings.disabled_mark_requires",
+		"需要将 `# ip 主机名` 行视为已禁用的主机名",
+	),
+	("settings.format", "格式"),
+	("settings.align_ip", "对齐 ip 列"),
+	("settings.one_host_per_line", "每行一个主机名"),
+	("settings.max_hosts_per_line", "每行最多主机名数"),
+	("settings.max_line_length", "最大行长度"),
+	("settings.no_limit", "0 表示不限制"),
+	("settings.spaces", "空格"),
+	("settings.tabs", "制表符"),
+	("settings.lint", "检查平台"),
+	("settings.lint_auto", "跟随系统（{0}）"),
+	("settings.shortcuts", "快捷键"),
+	("settings.press_keys", "请按键…"),
+	("settings.unbound", "无"),
+	("settings.record_shortcut", "点击以录制快捷键"),
+	("settings.clear_shortcut", "清除"),
+	("settings.restore_defaults", "恢复默认"),
+	("keymap.reserved", "已被文本编辑占用"),
+	("keymap.conflict", "已绑定到 `{0}`"),
+	// commands
+	("command.save", "保存"),
+	("command.reset", "重置"),
+	("command.reload", "重新加载 hosts 文件"),
+	("command.open_file", "打开文件"),
+	("command.save_as", "另存为"),
+	("command.close_tab", "关闭标签页"),
+	("command.format", "格式化"),
+	("command.normalize", "规范化"),
+	("command.new_item", "新建条目"),
+	("command.find_replace", "查找和替换"),
+	("command.settings", "设置"),
+	("command.command_palette", "命令面板"),
+	("command.audit", "安全检查"),
+	("command.options_view", "切换到选项视图"),
+	("command.text_view", "切换到文本视图"),
+	("command.toggle_host", "切换主机名"),
+	("command.jump_to_item", "跳转到条目"),
+	("command.go_to_item", "跳转到条目：{0}"),
+	("command.enable_host", "启用主机名：{0}（{1}）"),
+	("command.disable_host", "禁用主机名：{0}（{1}）"),
+	("palette.placeholder", "输入命令"),
+	("palette.no_match", "没有匹配的命令"),
+	// screen readers
+	("a11y.drag_item", "拖动以移动条目 {0}"),
+	("a11y.item_actions", "条目 {0} 的操作"),
+	("a11y.item_ip", "条目 {0} 的 IP 地址"),
+	("a11y.host_selected", "{0}，已选中"),
+	("a11y.hosts_content", "hosts 文件内容"),
+	("a11y.timer", "{1} 后恢复为{0}"),
+	("a11y.shortcut", "{0} 的快捷键：{1}"),
+	("a11y.clear_shortcut", "清除 {0} 的快捷键"),
+	("a11y.lints", "问题：{0}"),
+	// header
+	("header.github", "GitHub 上的 {0}"),
+	("header.light", "☀ 浅色"),
+	("header.dark", "🌙 深色"),
+];
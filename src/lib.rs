@@ -1,16 +1,59 @@
 pub mod consts;
 mod core;
+mod i18n;
 mod ui;
 mod util;
 
-pub use crate::core::Hed;
-use ui::{editor, editor_header, header};
+pub use crate::core::{Hed, Settings};
+use ui::{
+	command_palette, editor, editor_header, error_bar, file_tabs,
+	handle_shortcuts, header, set_fonts, set_style, toasts,
+};
 
 impl eframe::App for Hed {
 	fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+		if let Some(fonts) = self.take_loaded_fonts() {
+			set_fonts(ctx, fonts);
+		}
+		if self.take_style_changed() {
+			set_style(ctx, &self.settings);
+		}
+		if ctx.input(|i| i.viewport().close_requested())
+			&& !self.request_close()
+		{
+			ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+		}
+		if self.take_close_requested() {
+			ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+		}
+		if let Some(rect) = ctx.input(|i| i.viewport().inner_rect) {
+			self.settings.window_size = [rect.width(), rect.height()];
+		}
+		if let Some(after) = self.tick_timers() {
+			ctx.request_repaint_after(after);
+		}
+		if let Some(after) = self.tick_content_edit() {
+			ctx.request_repaint_after(after);
+		}
+		if let Some(after) = self.toasts.tick() {
+			ctx.request_repaint_after(after);
+		}
+		if let Some(after) = self.tick_autosave() {
+			ctx.request_repaint_after(after);
+		}
+		handle_shortcuts(ctx, self);
 		header(ctx, self);
+		file_tabs(ctx, self);
 		editor_header(ctx, self);
+		error_bar(ctx, self);
 		editor(ctx, self);
-		self.handle_event();
+		command_palette(ctx, self);
+		toasts(ctx, self);
+		self.handle_events();
+	}
+
+	fn on_exit(&mut self) {
+		self.autosave_on_exit();
+		self.save_settings();
 	}
 }
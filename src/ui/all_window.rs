@@ -1,21 +1,44 @@
-use egui::Ui;
+use egui::{
+	Align2, Button, DragValue, Event, Grid, Key, KeyboardShortcut, RichText,
+	ScrollArea, Ui, WidgetType, Window,
+};
 
-use super::component::{form_window, input, show_error_tooltip, text_area};
-use crate::core::{Hed, OpenedWindow};
+use super::{
+	common::a11y_label,
+	component::{
+		diff_view, form_label, form_window, input, show_error_tooltip,
+		text_area,
+	},
+};
+use crate::{
+	core::{
+		Command, DisabledMark, DiscardAction, Finding, Hed, HostsOptions,
+		Keymap, OpenedWindow, Platform, ReplaceTarget, RuleKind, Separator,
+		Severity, Theme, ViewKind,
+	},
+	i18n::{t, tf, Lang},
+	util::format_local_time,
+};
 
 pub fn new_item_window(ui: &mut Ui, hed: &mut Hed) {
 	if !hed.is_window_open(OpenedWindow::NewItem) {
 		return;
 	}
 
-	let window = form_window(ui, "New item", |ui| {
-		ui.heading("ip: ");
-		let ip_input = ui.add(input(&mut hed.item_form.ip, "ip", true));
+	let window = form_window(ui, t("window.new_item"), |ui| {
+		let label = form_label(ui, t("form.ip"));
+		let ip_input = ui
+			.add(input(&mut hed.item_form.ip, t("form.ip"), true))
+			.labelled_by(label.id);
 		show_error_tooltip(ip_input, &mut hed.item_form.ip_error);
 		ui.end_row();
-		ui.heading("hosts: ");
+		let label = form_label(ui, t("form.hosts"));
 		let hosts_input = ui
-			.add(text_area(&mut hed.item_form.hosts, "hosts").desired_rows(8));
+			.add(
+				text_area(&mut hed.item_form.hosts, t("form.hosts"))
+					.desired_rows(8),
+			)
+			.labelled_by(label.id);
 		show_error_tooltip(hosts_input, &mut hed.item_form.hosts_error);
 		ui.end_row();
 	});
@@ -34,10 +57,14 @@ pub fn add_hosts_window(ui: &mut Ui, hed: &mut Hed) {
 		return;
 	}
 
-	let window = form_window(ui, "Add Hosts", |ui| {
-		ui.heading("hosts: ");
+	let window = form_window(ui, t("window.add_hosts"), |ui| {
+		let label = form_label(ui, t("form.hosts"));
 		let hosts_input = ui
-			.add(text_area(&mut hed.item_form.hosts, "hosts").desired_rows(8));
+			.add(
+				text_area(&mut hed.item_form.hosts, t("form.hosts"))
+					.desired_rows(8),
+			)
+			.labelled_by(label.id);
 		show_error_tooltip(hosts_input, &mut hed.item_form.hosts_error);
 		ui.end_row();
 	});
@@ -56,10 +83,11 @@ pub fn edit_host_window(ui: &mut Ui, hed: &mut Hed) {
 		return;
 	}
 
-	let window = form_window(ui, "Edit Host", |ui| {
-		ui.heading("hosts: ");
-		let hosts_input =
-			ui.add(input(&mut hed.item_form.hosts, "hosts", true));
+	let window = form_window(ui, t("window.edit_host"), |ui| {
+		let label = form_label(ui, t("form.hosts"));
+		let hosts_input = ui
+			.add(input(&mut hed.item_form.hosts, t("form.hosts"), true))
+			.labelled_by(label.id);
 		show_error_tooltip(hosts_input, &mut hed.item_form.hosts_error);
 		ui.end_row();
 	});
@@ -72,3 +100,695 @@ pub fn edit_host_window(ui: &mut Ui, hed: &mut Hed) {
 		hed.edit_host();
 	}
 }
+
+pub fn find_replace_window(ui: &mut Ui, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::FindReplace) {
+		return;
+	}
+
+	let replacements = hed.find_replace_preview();
+
+	let window = form_window(ui, t("window.find_replace"), |ui| {
+		let form = &mut hed.find_replace;
+		let label = form_label(ui, t("form.find"));
+		let find_input = ui
+			.add(input(&mut form.find, t("form.find"), true))
+			.labelled_by(label.id);
+		show_error_tooltip(find_input, &mut form.find_error);
+		ui.end_row();
+		let label = form_label(ui, t("form.replace"));
+		ui.add(input(&mut form.replace, t("form.replace"), true))
+			.labelled_by(label.id);
+		ui.end_row();
+		form_label(ui, t("form.in"));
+		ui.horizontal_wrapped(|ui| {
+			ui.radio_value(&mut form.target, ReplaceTarget::Ip, t("form.ip"));
+			ui.radio_value(
+				&mut form.target,
+				ReplaceTarget::Host,
+				t("form.hosts"),
+			);
+			ui.radio_value(
+				&mut form.target,
+				ReplaceTarget::Text,
+				t("form.text"),
+			);
+			ui.checkbox(&mut form.use_regex, t("form.regex"));
+		});
+		ui.end_row();
+		form_label(ui, t("form.preview"));
+		ui.vertical(|ui| {
+			if replacements.is_empty() {
+				ui.weak(t("form.no_match"));
+			}
+			for replacement in &replacements {
+				let text = format!("{} → {}", replacement.old, replacement.new);
+				if replacement.valid {
+					ui.label(text);
+				} else {
+					ui.label(
+						RichText::new(text).color(ui.visuals().error_fg_color),
+					);
+				}
+			}
+		});
+		ui.end_row();
+	});
+
+	if window.close {
+		hed.close_find_replace_window();
+	}
+
+	if window.ok {
+		hed.apply_find_replace();
+	}
+}
+
+pub fn transfer_hosts_window(ui: &mut Ui, hed: &mut Hed) {
+	let title = if hed.is_window_open(OpenedWindow::MoveHosts) {
+		t("window.move_hosts")
+	} else if hed.is_window_open(OpenedWindow::CopyHosts) {
+		t("window.copy_hosts")
+	} else {
+		return;
+	};
+
+	let window = form_window(ui, title, |ui| {
+		let label = form_label(ui, t("form.to_ip"));
+		let ip_input = ui
+			.add(input(&mut hed.item_form.ip, t("form.ip"), true))
+			.labelled_by(label.id);
+		show_error_tooltip(ip_input, &mut hed.item_form.ip_error);
+		ui.end_row();
+	});
+
+	if window.close {
+		hed.close_item_form_window();
+	}
+
+	if window.ok {
+		hed.transfer_selected_hosts();
+	}
+}
+
+/// Asks for a path, to open a hosts file or to save the draft to.
+pub fn path_window(ui: &mut Ui, hed: &mut Hed) {
+	let Some(window) = hed.opened_window.filter(|window| {
+		matches!(
+			window,
+			OpenedWindow::OpenFile
+				| OpenedWindow::SaveAs
+				| OpenedWindow::ImportAuditRules
+		)
+	}) else {
+		return;
+	};
+	let title = match window {
+		OpenedWindow::OpenFile => t("window.open_file"),
+		OpenedWindow::SaveAs => t("window.save_as"),
+		_ => t("window.import_audit_rules"),
+	};
+
+	let form = form_window(ui, title, |ui| {
+		let label = form_label(ui, t("form.path"));
+		let path_input = ui
+			.add(input(&mut hed.path_form.path, t("form.path"), true))
+			.labelled_by(label.id);
+		show_error_tooltip(path_input, &mut hed.path_form.path_error);
+		ui.end_row();
+	});
+
+	if form.close {
+		hed.close_path_window();
+	}
+
+	if form.ok {
+		match window {
+			OpenedWindow::OpenFile => hed.open_file(),
+			OpenedWindow::SaveAs => hed.save_as(),
+			_ => hed.import_audit_rules(),
+		}
+	}
+}
+
+pub fn diff_window(ui: &mut Ui, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::Diff) {
+		return;
+	}
+
+	let mut open = true;
+	Window::new(t("window.diff"))
+		.open(&mut open)
+		.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+		.collapsible(false)
+		.default_size([640.0, 420.0])
+		.show(ui.ctx(), |ui| {
+			ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+				diff_view(ui, &hed.diff);
+			});
+		});
+
+	if !open {
+		hed.close_diff_window();
+	}
+}
+
+pub fn normalize_window(ui: &mut Ui, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::Normalize) {
+		return;
+	}
+
+	let mut open = true;
+	Window::new(t("window.normalize"))
+		.open(&mut open)
+		.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+		.collapsible(false)
+		.default_size([640.0, 420.0])
+		.show(ui.ctx(), |ui| {
+			let steps = &mut hed.normalize_steps;
+			let mut changed = false;
+			ui.horizontal_wrapped(|ui| {
+				for (value, label) in [
+					(&mut steps.lowercase, "normalize.lowercase"),
+					(&mut steps.merge_ips, "normalize.merge_ips"),
+					(&mut steps.dedupe, "normalize.dedupe"),
+					(&mut steps.sort, "normalize.sort"),
+					(&mut steps.move_comments, "normalize.move_comments"),
+					(&mut steps.collapse_blank, "normalize.collapse_blank"),
+				] {
+					changed |= ui.checkbox(value, t(label)).changed();
+				}
+			});
+			if changed {
+				hed.preview_normalize();
+			}
+			ui.horizontal(|ui| {
+				let has_changes = !hed.diff.is_empty();
+				if ui
+					.add_enabled(has_changes, Button::new(t("normalize.apply")))
+					.clicked()
+				{
+					hed.apply_normalize();
+				}
+				if ui.button(t("confirm.cancel")).clicked() {
+					hed.close_normalize_window();
+				}
+				if !has_changes {
+					ui.label(RichText::new(t("normalize.no_changes")).weak());
+				}
+			});
+			ui.separator();
+			ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+				diff_view(ui, &hed.diff);
+			});
+		});
+
+	if !open {
+		hed.close_normalize_window();
+	}
+}
+
+/// Save, discard or keep the draft before it would be dropped.
+pub fn confirm_discard_window(ui: &mut Ui, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::ConfirmDiscard) {
+		return;
+	}
+	let message = match hed.pending_discard {
+		Some(DiscardAction::Close) => t("confirm.close"),
+		Some(DiscardAction::CloseTab) => t("confirm.close_tab"),
+		Some(DiscardAction::Reload) => t("confirm.reload"),
+		Some(DiscardAction::ChangeHostsPath) => t("confirm.hosts_path"),
+		None => return,
+	};
+
+	let mut open = true;
+	Window::new(t("window.unsaved"))
+		.open(&mut open)
+		.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+		.collapsible(false)
+		.resizable(false)
+		.show(ui.ctx(), |ui| {
+			ui.label(message);
+			ui.add_space(8.0);
+			ui.horizontal(|ui| {
+				if ui
+					.add_enabled(hed.can_save(), Button::new(t("confirm.save")))
+					.clicked()
+				{
+					hed.confirm_save();
+				}
+				if ui.button(t("confirm.discard")).clicked() {
+					hed.confirm_discard_draft();
+				}
+				if ui.button(t("confirm.cancel")).clicked() {
+					hed.cancel_discard();
+				}
+			});
+		});
+
+	if !open {
+		hed.cancel_discard();
+	}
+}
+
+/// Make sure protected hosts are meant to be changed, one event at a time.
+pub fn confirm_protected_window(ui: &mut Ui, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::ConfirmProtected) {
+		return;
+	}
+	let message = tf(
+		"confirm.protected",
+		&[&hed.pending_protected_names().join(", ")],
+	);
+
+	let mut open = true;
+	Window::new(t("window.protected"))
+		.open(&mut open)
+		.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+		.collapsible(false)
+		.resizable(false)
+		.show(ui.ctx(), |ui| {
+			ui.label(message);
+			ui.add_space(8.0);
+			ui.horizontal(|ui| {
+				if ui.button(t("confirm.change_anyway")).clicked() {
+					hed.confirm_protected();
+				}
+				if ui.button(t("confirm.cancel")).clicked() {
+					hed.cancel_protected();
+				}
+			});
+		});
+
+	if !open {
+		hed.cancel_protected();
+	}
+}
+
+/// Suspicious hosts of the draft, with quick actions to disable them.
+pub fn audit_window(ui: &mut Ui, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::Audit) {
+		return;
+	}
+	hed.refresh_audit();
+
+	let mut open = true;
+	Window::new(t("window.audit"))
+		.open(&mut open)
+		.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+		.collapsible(false)
+		.default_size([640.0, 420.0])
+		.show(ui.ctx(), |ui| {
+			ui.horizontal(|ui| {
+				if let Some(rules) = hed.audit_rules() {
+					let source = if rules.imported {
+						t("audit.imported_rules")
+					} else {
+						t("audit.bundled_rules")
+					};
+					ui.label(
+						RichText::new(tf(
+							"audit.rules_version",
+							&[&source, &rules.version],
+						))
+						.weak(),
+					);
+				}
+				if ui.button(t("audit.import_rules")).clicked() {
+					hed.open_path_window(OpenedWindow::ImportAuditRules);
+				}
+			});
+			ui.separator();
+
+			if hed.findings.is_empty() {
+				ui.label(t("audit.no_findings"));
+				return;
+			}
+			ui.horizontal(|ui| {
+				ui.label(tf("audit.findings", &[&hed.findings.len()]));
+				if ui.button(t("audit.disable_all")).clicked() {
+					let keys = hed.findings.iter().map(|f| f.key).collect();
+					hed.disable_findings(keys);
+				}
+			});
+			ScrollArea::vertical().auto_shrink(false).show(ui, |ui| {
+				Grid::new("audit_findings").striped(true).show(ui, |ui| {
+					for finding in hed.findings.clone() {
+						finding_row(ui, hed, &finding);
+						ui.end_row();
+					}
+				});
+			});
+		});
+
+	if !open {
+		hed.close_audit_window();
+	}
+}
+
+fn finding_row(ui: &mut Ui, hed: &mut Hed, finding: &Finding) {
+	let (icon, color) = match finding.severity {
+		Severity::High => ("⚠", ui.visuals().error_fg_color),
+		Severity::Medium => ("⚠", ui.visuals().warn_fg_color),
+		Severity::Low => ("ℹ", ui.visuals().text_color()),
+	};
+	ui.label(RichText::new(icon).color(color));
+	ui.label(&finding.host);
+	ui.label(&finding.ip);
+	let kind = match finding.kind {
+		RuleKind::Redirect => t("audit.redirect"),
+		RuleKind::Blocked => t("audit.blocked"),
+	};
+	ui.label(kind).on_hover_text(&finding.rule_name);
+	ui.horizontal(|ui| {
+		if ui.small_button(t("options.disable")).clicked() {
+			hed.disable_findings(vec![finding.key]);
+		}
+		if ui.small_button(t("command.jump_to_item")).clicked() {
+			hed.close_audit_window();
+			hed.run_command(Command::JumpToItem(finding.key.0));
+		}
+	});
+}
+
+/// Offer the draft the last run left unsaved, with what it changes.
+pub fn restore_draft_window(ui: &mut Ui, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::RestoreDraft) {
+		return;
+	}
+	let Some(saved_at) = hed.restore.as_ref().map(|draft| draft.saved_at)
+	else {
+		return;
+	};
+
+	Window::new(t("window.restore_draft"))
+		.anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+		.collapsible(false)
+		.default_size([640.0, 420.0])
+		.show(ui.ctx(), |ui| {
+			ui.label(tf(
+				"restore.message",
+				&[&hed.hosts_path.display(), &format_local_time(saved_at)],
+			));
+			ui.horizontal(|ui| {
+				if ui.button(t("restore.restore")).clicked() {
+					hed.restore_draft();
+				}
+				if ui.button(t("restore.discard")).clicked() {
+					hed.discard_autosave();
+				}
+			});
+			ui.separator();
+			ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+				diff_view(ui, &hed.diff);
+			});
+		});
+}
+
+pub fn settings_window(ui: &mut Ui, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::Settings) {
+		return;
+	}
+
+	let window = form_window(ui, t("window.settings"), |ui| {
+		let settings = &mut hed.settings_draft;
+		form_label(ui, t("settings.language"));
+		language_ui(ui, &mut settings.language);
+		ui.end_row();
+		form_label(ui, t("settings.theme"));
+		ui.horizontal(|ui| {
+			ui.radio_value(
+				&mut settings.theme,
+				Theme::Light,
+				t("settings.light"),
+			);
+			ui.radio_value(
+				&mut settings.theme,
+				Theme::Dark,
+				t("settings.dark"),
+			);
+		});
+		ui.end_row();
+		form_label(ui, t("settings.view"));
+		ui.horizontal(|ui| {
+			ui.radio_value(
+				&mut settings.view_kind,
+				ViewKind::Options,
+				t("settings.options"),
+			);
+			ui.radio_value(
+				&mut settings.view_kind,
+				ViewKind::Text,
+				t("settings.text"),
+			);
+		});
+		ui.end_row();
+		let label = form_label(ui, t("settings.font_size"));
+		ui.add(DragValue::new(&mut settings.font_size).range(10.0..=32.0))
+			.labelled_by(label.id);
+		ui.end_row();
+		let label = form_label(ui, t("settings.font_file"));
+		ui.add(input(
+			&mut settings.font_path,
+			t("settings.system_fonts"),
+			true,
+		))
+		.labelled_by(label.id);
+		ui.end_row();
+		let label = form_label(ui, t("settings.hosts_file"));
+		ui.add(input(
+			&mut settings.hosts_path,
+			t("settings.system_hosts_file"),
+			true,
+		))
+		.labelled_by(label.id);
+		ui.end_row();
+		let label = form_label(ui, t("settings.protected_hosts"));
+		ui.add(input(
+			&mut settings.protected_hosts,
+			t("settings.protected_hint"),
+			true,
+		))
+		.labelled_by(label.id)
+		.on_hover_text(t("settings.protected_hint"));
+		ui.end_row();
+		form_label(ui, t("settings.parse"));
+		ui.vertical(|ui| {
+			hosts_options_ui(ui, &mut settings.hosts_options);
+		});
+		ui.end_row();
+		form_label(ui, t("settings.format"));
+		ui.vertical(|ui| {
+			format_options_ui(ui, &mut settings.hosts_options);
+		});
+		ui.end_row();
+		form_label(ui, t("settings.lint"));
+		lint_platform_ui(ui, &mut settings.lint_platform);
+		ui.end_row();
+		form_label(ui, t("settings.shortcuts"));
+		ui.vertical(|ui| {
+			keymap_ui(ui, &mut settings.keymap, &mut hed.recording_shortcut);
+		});
+		ui.end_row();
+	});
+
+	if window.close {
+		hed.close_settings_window();
+	}
+
+	if window.ok {
+		hed.apply_settings();
+	}
+}
+
+fn language_ui(ui: &mut Ui, language: &mut Option<Lang>) {
+	ui.horizontal_wrapped(|ui| {
+		ui.radio_value(language, None, t("settings.language_auto"));
+		for lang in Lang::ALL {
+			ui.radio_value(language, Some(lang), lang.native_name());
+		}
+	});
+}
+
+fn lint_platform_ui(ui: &mut Ui, platform: &mut Option<Platform>) {
+	ui.horizontal_wrapped(|ui| {
+		let auto = tf("settings.lint_auto", &[&Platform::current().name()]);
+		ui.radio_value(platform, None, auto);
+		for p in Platform::ALL {
+			ui.radio_value(platform, Some(p), p.name());
+		}
+	});
+}
+
+fn hosts_options_ui(ui: &mut Ui, options: &mut HostsOptions) {
+	ui.checkbox(&mut options.parse_commented, t("settings.parse_commented"));
+	ui.label(t("settings.disabled_mark"));
+	ui.horizontal(|ui| {
+		ui.radio_value(
+			&mut options.disabled_mark,
+			DisabledMark::Hed,
+			DisabledMark::Hed.as_str(),
+		);
+		ui.add_enabled_ui(options.parse_commented, |ui| {
+			ui.radio_value(
+				&mut options.disabled_mark,
+				DisabledMark::Plain,
+				DisabledMark::Plain.as_str(),
+			)
+			.on_disabled_hover_text(t("settings.disabled_mark_requires"));
+		});
+	});
+
+	if !options.parse_commented {
+		options.disabled_mark = DisabledMark::Hed;
+	}
+}
+
+fn format_options_ui(ui: &mut Ui, options: &mut HostsOptions) {
+	let format = &mut options.format;
+	ui.checkbox(&mut format.align_ip, t("settings.align_ip"));
+	ui.checkbox(
+		&mut format.one_host_per_line,
+		t("settings.one_host_per_line"),
+	);
+	ui.add_enabled_ui(!format.one_host_per_line, |ui| {
+		ui.horizontal(|ui| {
+			let value = ui.add(DragValue::new(&mut format.max_hosts_per_line));
+			let label = ui.label(t("settings.max_hosts_per_line"));
+			value.labelled_by(label.id);
+		});
+	});
+	ui.horizontal(|ui| {
+		let value = ui.add(DragValue::new(&mut format.max_line_length));
+		let label = ui.label(t("settings.max_line_length"));
+		value.labelled_by(label.id);
+	});
+	ui.weak(t("settings.no_limit"));
+	ui.horizontal(|ui| {
+		ui.radio_value(
+			&mut format.separator,
+			Separator::Space,
+			t("settings.spaces"),
+		);
+		ui.radio_value(
+			&mut format.separator,
+			Separator::Tab,
+			t("settings.tabs"),
+		);
+	});
+}
+
+fn keymap_ui(
+	ui: &mut Ui,
+	keymap: &mut Keymap,
+	recording: &mut Option<Command>,
+) {
+	if let Some(command) = recording.clone() {
+		let pressed = ui.input_mut(|i| {
+			let pressed = i.events.iter().find_map(|event| match event {
+				Event::Key {
+					key,
+					pressed: true,
+					modifiers,
+					..
+				} => Some(KeyboardShortcut::new(*modifiers, *key)),
+				_ => None,
+			});
+			if pressed.is_some() {
+				i.events.retain(|event| !matches!(event, Event::Key { .. }));
+			}
+			pressed
+		});
+		match pressed {
+			Some(shortcut) if shortcut.logical_key == Key::Escape => {
+				*recording = None;
+			}
+			Some(shortcut) => {
+				keymap.set(&command, Some(shortcut));
+				*recording = None;
+			}
+			None => {}
+		}
+	}
+
+	let commands: Vec<Command> =
+		keymap.iter().map(|(command, _)| command.clone()).collect();
+
+	for command in commands {
+		ui.horizontal(|ui| {
+			let text = if recording.as_ref() == Some(&command) {
+				t("settings.press_keys").to_string()
+			} else {
+				keymap
+					.get(&command)
+					.map(|s| ui.ctx().format_shortcut(&s))
+					.unwrap_or_else(|| t("settings.unbound").to_string())
+			};
+			let record = ui
+				.button(&text)
+				.on_hover_text(t("settings.record_shortcut"));
+			a11y_label(
+				&record,
+				WidgetType::Button,
+				&tf("a11y.shortcut", &[&command.label(), &text]),
+			);
+			if record.clicked() {
+				*recording = Some(command.clone());
+			}
+			let clear = ui
+				.small_button("×")
+				.on_hover_text(t("settings.clear_shortcut"));
+			a11y_label(
+				&clear,
+				WidgetType::Button,
+				&tf("a11y.clear_shortcut", &[&command.label()]),
+			);
+			if clear.clicked() {
+				keymap.set(&command, None);
+			}
+			ui.label(command.label());
+		});
+		if let Some(conflict) = keymap.conflict(&command) {
+			ui.label(
+				RichText::new(conflict).color(ui.visuals().error_fg_color),
+			);
+		}
+	}
+
+	if ui.button(t("settings.restore_defaults")).clicked() {
+		*keymap = Keymap::default();
+	}
+}
+
+pub fn timer_window(ui: &mut Ui, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::Timer) {
+		return;
+	}
+
+	let window = form_window(ui, t("window.timer"), |ui| {
+		let form = &mut hed.timer_form;
+		form_label(ui, t("form.state"));
+		ui.horizontal(|ui| {
+			ui.radio_value(&mut form.enable, true, t("options.enable"));
+			ui.radio_value(&mut form.enable, false, t("options.disable"));
+		});
+		ui.end_row();
+		let label = form_label(ui, t("form.until"));
+		let until_input = ui
+			.add(input(&mut form.until, t("form.until_hint"), true))
+			.labelled_by(label.id);
+		show_error_tooltip(until_input, &mut form.until_error);
+		ui.end_row();
+		ui.label("");
+		ui.weak(t("form.timer_note"));
+		ui.end_row();
+	});
+
+	if window.close {
+		hed.close_timer_window();
+	}
+
+	if window.ok {
+		hed.apply_timer();
+	}
+}
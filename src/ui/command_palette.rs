@@ -0,0 +1,100 @@
+use egui::{vec2, Align2, Button, Context, Key, Modifiers, ScrollArea, Window};
+
+use super::{common::command_shortcut_text, component::input};
+use crate::{
+	core::{Hed, OpenedWindow},
+	i18n::t,
+};
+
+pub fn command_palette(ctx: &Context, hed: &mut Hed) {
+	if !hed.is_window_open(OpenedWindow::CommandPalette) {
+		return;
+	}
+
+	let entries: Vec<_> = hed
+		.command_palette
+		.filter(hed.commands())
+		.into_iter()
+		.map(|entry| {
+			let shortcut = command_shortcut_text(ctx, hed, &entry.command);
+			(entry, shortcut)
+		})
+		.collect();
+	let palette = &mut hed.command_palette;
+	let last = entries.len().saturating_sub(1);
+
+	let (up, down, enter, escape) = ctx.input_mut(|i| {
+		(
+			i.consume_key(Modifiers::NONE, Key::ArrowUp),
+			i.consume_key(Modifiers::NONE, Key::ArrowDown),
+			i.consume_key(Modifiers::NONE, Key::Enter),
+			i.consume_key(Modifiers::NONE, Key::Escape),
+		)
+	});
+
+	if up {
+		palette.selected = palette.selected.saturating_sub(1);
+	}
+	if down {
+		palette.selected += 1;
+	}
+	palette.selected = palette.selected.min(last);
+
+	let mut command = None;
+
+	Window::new("command_palette")
+		.title_bar(false)
+		.anchor(Align2::CENTER_TOP, [0.0, 60.0])
+		.collapsible(false)
+		.resizable(false)
+		.fixed_size([480.0, 0.0])
+		.show(ctx, |ui| {
+			let query = ui.add(input(
+				&mut palette.query,
+				t("palette.placeholder"),
+				true,
+			));
+			query.request_focus();
+			if query.changed() {
+				palette.selected = 0;
+			}
+
+			ui.separator();
+
+			ScrollArea::vertical()
+				.id_source("command_palette_scroll_area")
+				.max_height(360.0)
+				.show(ui, |ui| {
+					if entries.is_empty() {
+						ui.weak(t("palette.no_match"));
+					}
+					for (i, (entry, shortcut)) in entries.iter().enumerate() {
+						let btn = ui.add(
+							Button::new(&entry.label)
+								.shortcut_text(shortcut)
+								.selected(i == palette.selected)
+								.frame(i == palette.selected)
+								.min_size(vec2(ui.available_width(), 0.0)),
+						);
+						if i == palette.selected && (up || down) {
+							btn.scroll_to_me(None);
+						}
+						if btn.clicked() {
+							command = Some(entry.command.clone());
+						}
+					}
+				});
+		});
+
+	if enter {
+		command = entries
+			.get(palette.selected)
+			.map(|(entry, _)| entry.command.clone());
+	}
+
+	if escape {
+		hed.close_command_palette();
+	} else if let Some(command) = command {
+		hed.run_command(command);
+	}
+}
@@ -1,4 +1,9 @@
-use egui::{Key, KeyboardShortcut, Modifiers, Ui};
+use egui::{
+	Button, Context, FontData, FontDefinitions, FontFamily, Response,
+	TextStyle, Ui, Visuals, WidgetInfo, WidgetType,
+};
+
+use crate::core::{Command, Hed, LoadedFonts, Settings, Theme};
 
 pub fn set_button_padding(ui: &mut Ui) {
 	let spacing = ui.spacing_mut();
@@ -6,14 +11,137 @@ pub fn set_button_padding(ui: &mut Ui) {
 	spacing.button_padding.y = 6.0;
 }
 
-pub fn save_btn_shortcut() -> KeyboardShortcut {
-	KeyboardShortcut::new(Modifiers::CTRL, Key::S)
+pub fn command_shortcut_text(
+	ctx: &Context,
+	hed: &Hed,
+	command: &Command,
+) -> String {
+	hed.settings
+		.keymap
+		.get(command)
+		.map(|shortcut| ctx.format_shortcut(&shortcut))
+		.unwrap_or_default()
+}
+
+/// A button running `command`, with the active shortcut in its tooltip and,
+/// unless `icon` is set, next to its text.
+pub fn command_button(
+	ui: &mut Ui,
+	hed: &Hed,
+	text: &str,
+	command: Command,
+	enabled: bool,
+	icon: bool,
+) -> Response {
+	let shortcut = command_shortcut_text(ui.ctx(), hed, &command);
+	let mut btn = Button::new(text);
+	if !icon {
+		btn = btn.shortcut_text(&shortcut);
+	}
+	let tooltip = if shortcut.is_empty() {
+		command.label().to_string()
+	} else {
+		format!("{} ({})", command.label(), shortcut)
+	};
+
+	let resp = ui
+		.add_enabled(enabled, btn)
+		.on_hover_text(&tooltip)
+		.on_disabled_hover_text(tooltip);
+	if icon {
+		a11y_label(&resp, WidgetType::Button, command.label());
+	}
+
+	resp
+}
+
+/// Name a widget for screen readers, for ones showing only an icon or
+/// whose text alone is ambiguous.
+pub fn a11y_label(resp: &Response, typ: WidgetType, label: &str) {
+	resp.widget_info(|| WidgetInfo::labeled(typ, resp.enabled(), label));
 }
 
-pub fn reset_btn_shortcut() -> KeyboardShortcut {
-	KeyboardShortcut::new(Modifiers::CTRL, Key::R)
+/// Expose a widget as a checkbox, so its on/off state is announced.
+pub fn a11y_checkbox(resp: &Response, label: &str, checked: bool) {
+	resp.widget_info(|| {
+		WidgetInfo::selected(
+			WidgetType::Checkbox,
+			resp.enabled(),
+			checked,
+			label,
+		)
+	});
 }
 
-pub fn format_btn_shortcut() -> KeyboardShortcut {
-	KeyboardShortcut::new(Modifiers::SHIFT | Modifiers::ALT, Key::F)
+/// Run the command bound to a pressed shortcut, in whichever view is shown.
+pub fn handle_shortcuts(ctx: &Context, hed: &mut Hed) {
+	if hed.opened_window.is_some() || hed.sys_hosts_loading {
+		return;
+	}
+
+	let keymap = &hed.settings.keymap;
+	let command =
+		keymap
+			.by_priority()
+			.into_iter()
+			.find_map(|(command, shortcut)| {
+				ctx.input_mut(|i| i.consume_shortcut(&shortcut))
+					.then(|| command.clone())
+			});
+
+	if let Some(command) = command {
+		hed.run_command(command);
+	}
+}
+
+pub fn set_style(ctx: &Context, settings: &Settings) {
+	ctx.set_visuals(match settings.theme {
+		Theme::Light => Visuals::light(),
+		Theme::Dark => Visuals::dark(),
+	});
+	ctx.style_mut(|style| {
+		for (text_style, font) in &mut style.text_styles {
+			if matches!(
+				text_style,
+				TextStyle::Body | TextStyle::Button | TextStyle::Monospace
+			) {
+				font.size = settings.font_size;
+			}
+		}
+	});
+}
+
+/// Use the custom font first, then the bundled fonts, then system fonts for
+/// the scripts the bundled ones don't cover.
+pub fn set_fonts(ctx: &Context, loaded: LoadedFonts) {
+	let mut fonts = FontDefinitions::default();
+
+	if let Some(Ok(font_data)) = loaded.custom {
+		let font_data = FontData::from_owned(font_data);
+		add_font(&mut fonts, "custom".to_string(), font_data, true);
+	}
+
+	for (name, font_data) in loaded.fallbacks {
+		add_font(&mut fonts, name, FontData::from_owned(font_data), false);
+	}
+
+	ctx.set_fonts(fonts);
+}
+
+fn add_font(
+	fonts: &mut FontDefinitions,
+	name: String,
+	font_data: FontData,
+	first: bool,
+) {
+	fonts.font_data.insert(name.clone(), font_data);
+
+	for family in [FontFamily::Proportional, FontFamily::Monospace] {
+		let names = fonts.families.entry(family).or_default();
+		if first {
+			names.insert(0, name.clone());
+		} else {
+			names.push(name.clone());
+		}
+	}
 }
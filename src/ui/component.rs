@@ -1,8 +1,17 @@
 use egui::{
-	Align, Align2, Frame, Grid, Key, Layout, Margin, Response, RichText,
-	ScrollArea, TextEdit, Ui, Window,
+	Align, Align2, Color32, Frame, Grid, Key, Layout, Margin, Response,
+	RichText, ScrollArea, TextEdit, Ui, WidgetType, Window,
 };
 
+use super::common::a11y_label;
+use crate::{
+	core::{DiffLine, DiffTag},
+	i18n::t,
+};
+
+/// egui has no success color, this green reads on both themes.
+pub const SUCCESS_COLOR: Color32 = Color32::from_rgb(0x2e, 0xa0, 0x43);
+
 pub fn div(
 	ui: &mut Ui,
 	margin: impl Into<Margin>,
@@ -63,14 +72,19 @@ pub fn form_window(
 												.with_cross_align(Align::Center)
 												.with_cross_justify(true),
 											|ui| {
-												if ui
-													.selectable_label(
-														true,
-														RichText::new("OK")
-															.size(16.0),
-													)
-													.clicked()
-												{
+												let ok = ui.selectable_label(
+													true,
+													RichText::new(t(
+														"window.ok",
+													))
+													.size(16.0),
+												);
+												a11y_label(
+													&ok,
+													WidgetType::Button,
+													t("window.ok"),
+												);
+												if ok.clicked() {
 													resp.ok = true;
 												}
 											},
@@ -90,6 +104,11 @@ pub fn form_window(
 	resp
 }
 
+/// The label of a form row, pass its id to `labelled_by` of the row input.
+pub fn form_label(ui: &mut Ui, text: &str) -> Response {
+	ui.heading(format!("{}: ", text))
+}
+
 pub fn input<'a>(
 	value: &'a mut String,
 	placeholder: &'static str,
@@ -124,3 +143,24 @@ pub fn show_error_tooltip(response: Response, error: &mut String) {
 		}
 	}
 }
+
+/// Lines of a diff in monospace, hunks apart.
+pub fn diff_view(ui: &mut Ui, hunks: &[Vec<DiffLine>]) {
+	for (idx, hunk) in hunks.iter().enumerate() {
+		if idx > 0 {
+			ui.separator();
+		}
+		for line in hunk {
+			let (mark, color) = match line.tag {
+				DiffTag::Equal => (' ', ui.visuals().weak_text_color()),
+				DiffTag::Delete => ('-', ui.visuals().error_fg_color),
+				DiffTag::Insert => ('+', SUCCESS_COLOR),
+			};
+			ui.label(
+				RichText::new(format!("{} {}", mark, line.text))
+					.monospace()
+					.color(color),
+			);
+		}
+	}
+}
@@ -1,34 +1,129 @@
-use egui::{Button, CentralPanel, Context, Margin, ScrollArea, TextEdit, Ui};
+use std::{collections::HashMap, ops::Range, sync::Arc};
+
+use egui::{
+	vec2, Align, Align2, Button, CentralPanel, Color32, Context, FontId, Label,
+	Layout, Pos2, Rect, RichText, ScrollArea, Sense, Stroke, TextEdit,
+	TextStyle, Ui, WidgetType,
+};
 
 use super::{
-	all_window::{add_hosts_window, edit_host_window, new_item_window},
-	common::{
-		format_btn_shortcut, reset_btn_shortcut, save_btn_shortcut,
-		set_button_padding,
+	all_window::{
+		add_hosts_window, audit_window, confirm_discard_window,
+		confirm_protected_window, diff_window, edit_host_window,
+		find_replace_window, new_item_window, normalize_window, path_window,
+		restore_draft_window, settings_window, timer_window,
+		transfer_hosts_window,
+	},
+	common::{a11y_checkbox, a11y_label, command_button, set_button_padding},
+	component::{input, SUCCESS_COLOR},
+	error_view::error_screen,
+	lint_view::{
+		lint_bar, lint_menu, lints_by_ip, text_lint_markers, LintsByIp,
 	},
-	component::{div, input},
 };
-use crate::core::{Event, Hed, OpenedWindow, ViewKind};
+use crate::{
+	core::{
+		Changes, Command, Event, Hed, Host, HostChange, HostKey, HostTimer,
+		Item, Lint, OpenedWindow, RemovedHost, ReplaceTarget, SelectMode,
+		TimerTarget, ViewKind,
+	},
+	i18n::{t, tf},
+	util::{format_countdown, format_local_time, now_secs},
+};
+
+/// Timers of the draft by ip and host name.
+type Timers<'a> = HashMap<(&'a str, &'a str), &'a HostTimer>;
+
+/// What the rows of the options view share.
+struct RowContext<'a> {
+	timers: &'a Timers<'a>,
+	changes: &'a Changes,
+	lints: &'a LintsByIp<'a>,
+	header_width: f32,
+	row_height: f32,
+}
+
+/// Room for the countdown next to a host with a timer.
+const TIMER_BADGE_WIDTH: f32 = 64.0;
+
+/// A line of the options view, the hosts of an item wrap into as many rows
+/// as they need. Hosts past the end of a draft item are the ones removed
+/// from it.
+#[derive(Clone)]
+struct Row {
+	item: RowItem,
+	hosts: Range<usize>,
+	is_first: bool,
+	is_last: bool,
+}
+
+#[derive(Clone, Copy)]
+enum RowItem {
+	/// Index in the draft list.
+	Draft(usize),
+	/// Index in the items removed from the draft.
+	Removed(usize),
+}
+
+/// Rows of the options view, rebuilt when the list, the saved file, the
+/// search or the width changes.
+#[derive(Clone)]
+struct RowsCache {
+	key: (usize, usize, String, u32),
+	rows: Arc<Vec<Row>>,
+}
+
+/// Changes of the draft, rebuilt when the draft or the saved file changes.
+#[derive(Clone)]
+struct ChangesCache {
+	key: (usize, usize),
+	changes: Arc<Changes>,
+}
+
+#[derive(Clone, Copy)]
+enum DragPayload {
+	Item(usize),
+	Host(HostKey),
+}
 
 pub fn editor(ctx: &Context, hed: &mut Hed) {
 	CentralPanel::default().show(ctx, |ui| {
-		if !hed.os_err.is_empty() {
-			ui.centered_and_justified(|ui| {
-				ui.heading(&hed.os_err);
+		set_button_padding(ui);
+		let enabled = !hed.sys_hosts_loading && hed.opened_window.is_none();
+		if hed.loaded {
+			ui.add_enabled_ui(enabled, |ui| {
+				views(ui, hed);
+			});
+		} else {
+			ui.add_enabled_ui(enabled, |ui| {
+				error_screen(ui, hed);
 			});
-			return;
 		}
-		ui.add_enabled_ui(
-			!hed.sys_hosts_loading && hed.opened_window.is_none(),
-			|ui| {
-				panel_content(ui, hed);
-			},
-		);
+
+		windows(ui, hed);
 	});
 }
 
-fn panel_content(ui: &mut Ui, hed: &mut Hed) {
-	set_button_padding(ui);
+fn windows(ui: &mut Ui, hed: &mut Hed) {
+	new_item_window(ui, hed);
+	add_hosts_window(ui, hed);
+	edit_host_window(ui, hed);
+	find_replace_window(ui, hed);
+	transfer_hosts_window(ui, hed);
+	settings_window(ui, hed);
+	timer_window(ui, hed);
+	path_window(ui, hed);
+	diff_window(ui, hed);
+	normalize_window(ui, hed);
+	confirm_discard_window(ui, hed);
+	confirm_protected_window(ui, hed);
+	audit_window(ui, hed);
+	restore_draft_window(ui, hed);
+}
+
+fn views(ui: &mut Ui, hed: &mut Hed) {
+	hed.refresh_lints();
+	lint_bar(ui, hed);
 
 	if hed.view_all {
 		let width = ui.available_width() - 20.0;
@@ -46,7 +141,7 @@ fn panel_content(ui: &mut Ui, hed: &mut Hed) {
 		return;
 	}
 
-	match hed.view_kind {
+	match hed.settings.view_kind {
 		ViewKind::Options => {
 			options_view(ui, hed);
 		}
@@ -59,132 +154,704 @@ fn panel_content(ui: &mut Ui, hed: &mut Hed) {
 fn options_view(ui: &mut Ui, hed: &mut Hed) {
 	ui.horizontal(|ui| {
 		ui.set_height(30.0);
-		if ui.button("+ New item").clicked() {
+		if command_button(
+			ui,
+			hed,
+			&format!("+ {}", t("window.new_item")),
+			Command::NewItem,
+			true,
+			true,
+		)
+		.clicked()
+		{
 			hed.set_opened_window(OpenedWindow::NewItem);
 		}
-		ui.add(input(&mut hed.search_ip_hosts, "Search ip, hosts", true));
+		if command_button(
+			ui,
+			hed,
+			t("options.replace"),
+			Command::FindReplace,
+			true,
+			true,
+		)
+		.clicked()
+		{
+			hed.open_find_replace_window(ReplaceTarget::Ip);
+		}
+		let search =
+			ui.add(input(&mut hed.search_ip_hosts, t("options.search"), true));
+		a11y_label(&search, WidgetType::TextEdit, t("options.search"));
 	});
 
+	if !hed.selection.is_empty() {
+		selection_bar(ui, hed);
+	}
+
 	ui.separator();
 
-	ScrollArea::vertical()
-		.id_source("options_view")
-		.show(ui, |ui| {
-			div(
-				ui,
-				Margin {
-					right: 8.0,
-					..Default::default()
-				},
+	let timers: Timers = hed
+		.hosts_info_draft
+		.timers()
+		.map(|timer| ((timer.ip.as_str(), timer.host.as_str()), timer))
+		.collect();
+	let row_height = row_height(ui);
+	// room for the drag handle and the menu next to the ip input
+	let header_width = ui.spacing().text_edit_width + 100.0;
+	// keep the hosts clear of the scroll bar
+	let hosts_width = ui.available_width() - header_width - 8.0;
+	let changes = options_changes(ui, hed);
+	let rows = options_rows(ui, hed, &timers, &changes, hosts_width);
+
+	let mut scroll_area = ScrollArea::vertical().id_source("options_view");
+	if let Some(row_idx) = hed.scroll_to_item.and_then(|item_id| {
+		rows.iter().position(|row| match row.item {
+			RowItem::Draft(idx) if row.is_first => {
+				hed.hosts_info_draft.list[idx].id == item_id
+			}
+			_ => false,
+		})
+	}) {
+		let row_span = row_height + ui.spacing().item_spacing.y;
+		scroll_area =
+			scroll_area.vertical_scroll_offset(row_idx as f32 * row_span);
+	}
+	let lints = lints_by_ip(&hed.lints);
+	let ctx = RowContext {
+		timers: &timers,
+		changes: &changes,
+		lints: &lints,
+		header_width,
+		row_height,
+	};
+	scroll_area.show_rows(ui, row_height, rows.len(), |ui, range| {
+		for row in &rows[range] {
+			row_view(ui, hed, &ctx, row);
+		}
+	});
+
+	hed.scroll_to_item = None;
+}
+
+fn row_height(ui: &Ui) -> f32 {
+	let spacing = ui.spacing();
+	let text_height = ui
+		.text_style_height(&TextStyle::Button)
+		.max(ui.text_style_height(&TextStyle::Body));
+	(text_height + spacing.button_padding.y * 2.0).max(spacing.interact_size.y)
+}
+
+fn options_changes(ui: &Ui, hed: &Hed) -> Arc<Changes> {
+	let id = ui.id().with("options_changes");
+	let key = (hed.hosts_info_draft.revision(), hed.hosts_info.revision());
+	if let Some(cache) = ui
+		.data(|d| d.get_temp::<ChangesCache>(id))
+		.filter(|cache| cache.key == key)
+	{
+		return cache.changes;
+	}
+
+	let changes = Arc::new(Changes::new(
+		&hed.hosts_info.list,
+		&hed.hosts_info_draft.list,
+	));
+	ui.data_mut(|d| {
+		d.insert_temp(
+			id,
+			ChangesCache {
+				key,
+				changes: changes.clone(),
+			},
+		);
+	});
+
+	changes
+}
+
+/// Wrap the hosts of the matching items into rows of `hosts_width`, only
+/// the rows in view are then built.
+fn options_rows(
+	ui: &Ui,
+	hed: &Hed,
+	timers: &Timers,
+	changes: &Changes,
+	hosts_width: f32,
+) -> Arc<Vec<Row>> {
+	let id = ui.id().with("options_rows");
+	let key = (
+		hed.hosts_info_draft.revision(),
+		hed.hosts_info.revision(),
+		hed.search_ip_hosts.clone(),
+		hosts_width.to_bits(),
+	);
+	if let Some(cache) = ui
+		.data(|d| d.get_temp::<RowsCache>(id))
+		.filter(|cache| cache.key == key)
+	{
+		return cache.rows;
+	}
+
+	let font_id = TextStyle::Button.resolve(ui.style());
+	let spacing = ui.spacing();
+	let padding = spacing.button_padding.x * 2.0 + spacing.item_spacing.x;
+	let search = hed.search_ip_hosts.as_str();
+	let mut rows = vec![];
+
+	ui.fonts(|fonts| {
+		let mut ascii_widths = [None; 128];
+		let mut char_width = |c: char| match ascii_widths.get_mut(c as usize) {
+			Some(width) => {
+				*width.get_or_insert_with(|| fonts.glyph_width(&font_id, c))
+			}
+			None => fonts.glyph_width(&font_id, c),
+		};
+
+		let mut wrap = |item: RowItem, widths: Vec<f32>| {
+			let first_row = rows.len();
+			let mut start = 0;
+			let mut end = 0;
+			let mut width = 0.0;
+			for (host_idx, host_width) in widths.into_iter().enumerate() {
+				if host_idx > start && width + host_width > hosts_width {
+					rows.push(Row {
+						item,
+						hosts: start..host_idx,
+						is_first: false,
+						is_last: false,
+					});
+					start = host_idx;
+					width = 0.0;
+				}
+				width += host_width;
+				end = host_idx + 1;
+			}
+			rows.push(Row {
+				item,
+				hosts: start..end,
+				is_first: false,
+				is_last: true,
+			});
+			rows[first_row].is_first = true;
+		};
+		let mut name_width = |name: &str| {
+			padding + name.chars().map(&mut char_width).sum::<f32>()
+		};
+
+		for (item_idx, item) in hed.hosts_info_draft.list.iter().enumerate() {
+			if !item.contains(search) {
+				continue;
+			}
+			let mut widths: Vec<f32> = item
+				.hosts
+				.iter()
+				.map(|host| {
+					let mut width = name_width(&host.name);
+					if timers
+						.contains_key(&(item.ip.as_str(), host.name.as_str()))
+					{
+						width += TIMER_BADGE_WIDTH;
+					}
+					width
+				})
+				.collect();
+			widths.extend(
+				changes
+					.removed(&item.ip)
+					.iter()
+					.map(|host| name_width(&host.name)),
+			);
+			wrap(RowItem::Draft(item_idx), widths);
+		}
+
+		for (idx, (ip, hosts)) in changes.removed_items().iter().enumerate() {
+			if !ip.contains(search)
+				&& !hosts.iter().any(|host| host.name.contains(search))
+			{
+				continue;
+			}
+			let widths =
+				hosts.iter().map(|host| name_width(&host.name)).collect();
+			wrap(RowItem::Removed(idx), widths);
+		}
+	});
+
+	let rows = Arc::new(rows);
+	ui.data_mut(|d| {
+		d.insert_temp(
+			id,
+			RowsCache {
+				key,
+				rows: rows.clone(),
+			},
+		);
+	});
+
+	rows
+}
+
+fn row_view(ui: &mut Ui, hed: &Hed, ctx: &RowContext, row: &Row) {
+	let RowContext {
+		timers,
+		changes,
+		header_width,
+		row_height,
+		..
+	} = *ctx;
+	let item_idx = match row.item {
+		RowItem::Draft(item_idx) => item_idx,
+		RowItem::Removed(idx) => {
+			let (ip, hosts) = &changes.removed_items()[idx];
+			let response = ui
+				.horizontal(|ui| {
+					ui.set_height(row_height);
+					ui.allocate_ui_with_layout(
+						vec2(header_width, row_height),
+						Layout::left_to_right(Align::Center),
+						|ui| {
+							ui.set_min_width(header_width);
+							if row.is_first {
+								removed_item_header(ui, hed, ip, hosts);
+							}
+						},
+					);
+					for host in &hosts[row.hosts.clone()] {
+						removed_host_view(ui, hed, ip, host);
+					}
+				})
+				.response;
+			row_separator(ui, row, response.rect);
+			return;
+		}
+	};
+	let item = &hed.hosts_info_draft.list[item_idx];
+	let removed = changes.removed(&item.ip);
+	let response = ui
+		.horizontal(|ui| {
+			ui.set_height(row_height);
+			ui.allocate_ui_with_layout(
+				vec2(header_width, row_height),
+				Layout::left_to_right(Align::Center),
 				|ui| {
-					for item in &hed.hosts_info_draft.list {
-						if !item.contains(&hed.search_ip_hosts) {
-							continue;
-						}
-						ui.horizontal(|ui| {
-							ui.vertical(|ui| {
-								ui.add_space(8.0);
-								ui.horizontal(|ui| {
-									ui.menu_button("⛭", |ui| {
-										set_button_padding(ui);
-
-										if ui.button("Add hosts").clicked() {
-											hed.send_event(
-												Event::OpenAddHostsWindow(
-													item.id,
-												),
-											);
-											ui.close_menu();
-										}
-										if ui.button("Delete").clicked() {
-											hed.send_event(Event::DeleteItem(
-												item.id,
-											));
-											ui.close_menu();
-										}
-										if ui.button("Enable all").clicked() {
-											hed.send_event(
-												Event::ToggleAllHostEnable(
-													item.id, true,
-												),
-											);
-											ui.close_menu();
-										}
-										if ui.button("Disable all").clicked() {
-											hed.send_event(
-												Event::ToggleAllHostEnable(
-													item.id, false,
-												),
-											);
-											ui.close_menu();
-										}
-									});
-									let mut ip = item.ip.clone();
-									let input =
-										ui.add(input(&mut ip, "ip", false));
-									if input.changed() {
-										hed.send_event(Event::EditItemIp(
-											item.id, ip,
-										));
-									}
-								});
-							});
-							ui.horizontal_wrapped(|ui| {
-								for host in &item.hosts {
-									let btn = if host.enabled {
-										ui.selectable_label(true, &host.name)
-									} else {
-										ui.button(&host.name)
-									};
-									if btn.clicked() {
-										hed.send_event(
-											Event::ToggleHostEnable(
-												item.id, host.id,
-											),
-										);
-									}
-									btn.context_menu(|ui| {
-										set_button_padding(ui);
-										if ui.button("Edit").clicked() {
-											hed.send_event(
-												Event::OpenEditHostWindow(
-													item.id, host.id,
-												),
-											);
-											ui.close_menu();
-										}
-										if ui.button("Delete").clicked() {
-											hed.send_event(Event::DeleteHost(
-												item.id, host.id,
-											));
-											ui.close_menu();
-										}
-									});
-								}
-							});
-						});
-						ui.separator();
+					ui.set_min_width(header_width);
+					if row.is_first {
+						let lints = ctx.lints.get(item.ip.as_str());
+						item_header(
+							ui,
+							hed,
+							item,
+							lints.map_or(&[], Vec::as_slice),
+						);
 					}
 				},
 			);
-		});
+			for host_idx in row.hosts.clone() {
+				let Some(host) = item.hosts.get(host_idx) else {
+					let host = &removed[host_idx - item.hosts.len()];
+					removed_host_view(ui, hed, &item.ip, host);
+					continue;
+				};
+				let ip_host = (item.ip.as_str(), host.name.as_str());
+				let timer = timers.get(&ip_host).copied();
+				let change = changes.host(&item.ip, &host.name);
+				host_view(ui, hed, item, host_idx, host, timer, change);
+			}
+		})
+		.response;
 
-	new_item_window(ui, hed);
-	add_hosts_window(ui, hed);
-	edit_host_window(ui, hed);
+	let rect = response.rect;
+	row_separator(ui, row, rect);
+
+	let after = pointer_after(ui, rect.center().y, |pos| pos.y);
+	// an item goes before the first row or after the last row of another
+	let item_droppable = (row.is_first && !after) || (row.is_last && after);
+
+	if item_droppable
+		&& response
+			.dnd_hover_payload::<DragPayload>()
+			.is_some_and(|payload| matches!(*payload, DragPayload::Item(_)))
+	{
+		let y = if after {
+			rect.bottom()
+		} else {
+			rect.top()
+		};
+		ui.painter()
+			.hline(rect.x_range(), y, ui.visuals().selection.stroke);
+	}
+
+	if let Some(payload) = response.dnd_release_payload::<DragPayload>() {
+		match *payload {
+			DragPayload::Item(item_id) if item_droppable => {
+				let to = if after {
+					item_idx + 1
+				} else {
+					item_idx
+				};
+				hed.send_event(Event::MoveItem(item_id, to));
+			}
+			DragPayload::Item(_) => {}
+			DragPayload::Host(key) => {
+				hed.send_event(Event::MoveHost(key, item.id, item.hosts.len()));
+			}
+		}
+	}
+}
+
+fn row_separator(ui: &Ui, row: &Row, rect: Rect) {
+	if row.is_last {
+		let y = rect.bottom() + ui.spacing().item_spacing.y / 2.0;
+		ui.painter().hline(
+			rect.x_range(),
+			y,
+			ui.visuals().widgets.noninteractive.bg_stroke,
+		);
+	}
+}
+
+fn item_header(ui: &mut Ui, hed: &Hed, item: &Item, lints: &[&Lint]) {
+	// dragging needs a pointer, keep the handle out of tab order
+	let handle = ui.add(Label::new("☰").sense(Sense {
+		focusable: false,
+		..Sense::drag()
+	}));
+	a11y_label(
+		&handle,
+		WidgetType::Label,
+		&tf("a11y.drag_item", &[&item.ip]),
+	);
+	handle.dnd_set_drag_payload(DragPayload::Item(item.id));
+	let menu = ui.menu_button("⛭", |ui| {
+		set_button_padding(ui);
+
+		if ui.button(t("options.add_hosts")).clicked() {
+			hed.send_event(Event::OpenAddHostsWindow(item.id));
+			ui.close_menu();
+		}
+		if ui.button(t("options.delete")).clicked() {
+			hed.send_event(Event::DeleteItem(item.id));
+			ui.close_menu();
+		}
+		if ui.button(t("options.enable_all")).clicked() {
+			hed.send_event(Event::ToggleAllHostEnable(item.id, true));
+			ui.close_menu();
+		}
+		if ui.button(t("options.disable_all")).clicked() {
+			hed.send_event(Event::ToggleAllHostEnable(item.id, false));
+			ui.close_menu();
+		}
+		if ui.button(t("options.temporary")).clicked() {
+			hed.send_event(Event::OpenTimerWindow(TimerTarget::Item(item.id)));
+			ui.close_menu();
+		}
+	});
+	a11y_label(
+		&menu.response,
+		WidgetType::Button,
+		&tf("a11y.item_actions", &[&item.ip]),
+	);
+	let mut ip = item.ip.clone();
+	let input = ui.add(input(&mut ip, t("form.ip"), false));
+	a11y_label(
+		&input,
+		WidgetType::TextEdit,
+		&tf("a11y.item_ip", &[&item.ip]),
+	);
+	if input.changed() {
+		hed.send_event(Event::EditItemIp(item.id, ip));
+	}
+	lint_menu(ui, hed, lints);
+}
+
+fn host_view(
+	ui: &mut Ui,
+	hed: &Hed,
+	item: &Item,
+	host_idx: usize,
+	host: &Host,
+	timer: Option<&HostTimer>,
+	change: Option<&HostChange>,
+) {
+	let key = (item.id, host.id);
+	let btn = if host.enabled {
+		ui.selectable_label(true, &host.name)
+	} else {
+		ui.button(&host.name)
+	};
+	let mut btn = btn.interact(Sense::drag());
+	btn.dnd_set_drag_payload(DragPayload::Host(key));
+
+	let selected = hed.selection.contains(key);
+	let mut label = if selected {
+		tf("a11y.host_selected", &[&host.name])
+	} else {
+		host.name.clone()
+	};
+	if let Some(change) = change {
+		let (color, text) = match change {
+			HostChange::Added => (SUCCESS_COLOR, t("change.added").to_string()),
+			HostChange::Renamed(from) => {
+				(ui.visuals().hyperlink_color, tf("change.renamed", &[from]))
+			}
+			HostChange::Toggled => {
+				(ui.visuals().warn_fg_color, t("change.toggled").to_string())
+			}
+		};
+		change_marker(ui, btn.rect, color);
+		label = format!("{}, {}", label, text);
+		btn = btn.on_hover_text(text);
+	}
+	if hed.is_protected(&host.name) {
+		lock_marker(ui, btn.rect);
+		label = format!("{}, {}", label, t("options.protected"));
+		btn = btn.on_hover_text(t("options.protected"));
+	}
+	a11y_checkbox(&btn, &label, host.enabled);
+
+	if selected {
+		ui.painter().rect_stroke(
+			btn.rect.expand(1.0),
+			2.0,
+			Stroke::new(2.0, ui.visuals().warn_fg_color),
+		);
+	}
+
+	let rect = btn.rect;
+	let after = pointer_after(ui, rect.center().x, |pos| pos.x);
+
+	if btn.dnd_hover_payload::<DragPayload>().is_some_and(
+		|payload| matches!(*payload, DragPayload::Host(k) if k != key),
+	) {
+		let x = if after {
+			rect.right() + 2.0
+		} else {
+			rect.left() - 2.0
+		};
+		ui.painter()
+			.vline(x, rect.y_range(), ui.visuals().selection.stroke);
+	}
+
+	if let Some(payload) = btn.dnd_release_payload::<DragPayload>() {
+		if let DragPayload::Host(from) = *payload {
+			if from != key {
+				let to = if after {
+					host_idx + 1
+				} else {
+					host_idx
+				};
+				hed.send_event(Event::MoveHost(from, item.id, to));
+			}
+		}
+	}
+
+	if btn.clicked() {
+		let modifiers = ui.input(|i| i.modifiers);
+		let event = if modifiers.command {
+			Event::SelectHost(key, SelectMode::Toggle)
+		} else if modifiers.shift {
+			Event::SelectHost(key, SelectMode::Range)
+		} else {
+			Event::ToggleHostEnable(item.id, host.id)
+		};
+		hed.send_event(event);
+	}
+
+	btn.context_menu(|ui| {
+		set_button_padding(ui);
+		if ui.button(t("options.edit")).clicked() {
+			hed.send_event(Event::OpenEditHostWindow(item.id, host.id));
+			ui.close_menu();
+		}
+		if ui.button(t("options.delete")).clicked() {
+			hed.send_event(Event::DeleteHost(item.id, host.id));
+			ui.close_menu();
+		}
+		if ui.button(t("options.temporary")).clicked() {
+			hed.send_event(Event::OpenTimerWindow(TimerTarget::Host(key)));
+			ui.close_menu();
+		}
+		if timer.is_some() && ui.button(t("options.cancel_timer")).clicked() {
+			hed.send_event(Event::CancelTimer(key));
+			ui.close_menu();
+		}
+		if let Some(change) = change {
+			if ui.button(t("options.revert")).clicked() {
+				hed.send_event(match change {
+					HostChange::Added => Event::DeleteHost(item.id, host.id),
+					HostChange::Renamed(from) => {
+						Event::RenameHost(item.id, host.id, from.clone())
+					}
+					HostChange::Toggled => {
+						Event::ToggleHostEnable(item.id, host.id)
+					}
+				});
+				ui.close_menu();
+			}
+		}
+	});
+
+	if let Some(timer) = timer {
+		timer_badge(ui, timer);
+	}
+}
+
+/// An underline in the color of the change, apart from the outline of a
+/// selected host.
+fn change_marker(ui: &Ui, rect: Rect, color: Color32) {
+	let y = rect.bottom() + 1.0;
+	ui.painter()
+		.hline(rect.x_range().shrink(2.0), y, Stroke::new(2.0, color));
+}
+
+/// A small lock in the corner of a protected host, it takes no room of
+/// its own so that the rows wrap the same.
+fn lock_marker(ui: &Ui, rect: Rect) {
+	ui.painter().text(
+		rect.right_top(),
+		Align2::RIGHT_TOP,
+		"🔒",
+		FontId::proportional(9.0),
+		ui.visuals().weak_text_color(),
+	);
+}
+
+fn removed_item_header(
+	ui: &mut Ui,
+	hed: &Hed,
+	ip: &str,
+	hosts: &[RemovedHost],
+) {
+	let revert = ui.small_button("↶").on_hover_text(t("options.revert"));
+	a11y_label(&revert, WidgetType::Button, t("options.revert"));
+	if revert.clicked() {
+		hed.send_event(Event::RestoreHosts(ip.to_string(), hosts.to_vec()));
+	}
+	ui.label(RichText::new(ip).strikethrough().weak())
+		.on_hover_text(t("change.removed"));
+}
+
+/// A ghost of a host removed since the last save.
+fn removed_host_view(ui: &mut Ui, hed: &Hed, ip: &str, host: &RemovedHost) {
+	let btn = ui
+		.add(
+			Button::new(RichText::new(&host.name).strikethrough().weak())
+				.frame(false),
+		)
+		.on_hover_text(t("change.removed"));
+	a11y_label(
+		&btn,
+		WidgetType::Button,
+		&format!("{}, {}", host.name, t("change.removed")),
+	);
+	btn.context_menu(|ui| {
+		set_button_padding(ui);
+		if ui.button(t("options.revert")).clicked() {
+			hed.send_event(Event::RestoreHosts(
+				ip.to_string(),
+				vec![host.clone()],
+			));
+			ui.close_menu();
+		}
+	});
+}
+
+fn timer_badge(ui: &mut Ui, timer: &HostTimer) {
+	let remaining = format_countdown(timer.remaining(now_secs()));
+	let state = if timer.revert_to {
+		t("timer.enabled")
+	} else {
+		t("timer.disabled")
+	};
+	let badge = ui
+		.label(
+			RichText::new(format!("⏱ {}", remaining))
+				.small()
+				.color(ui.visuals().warn_fg_color),
+		)
+		.on_hover_text(tf(
+			"timer.tooltip",
+			&[&state, &format_local_time(timer.expires_at)],
+		));
+	a11y_label(
+		&badge,
+		WidgetType::Label,
+		&tf("a11y.timer", &[&state, &remaining]),
+	);
+}
+
+fn pointer_after(ui: &Ui, center: f32, axis: impl Fn(Pos2) -> f32) -> bool {
+	ui.ctx()
+		.pointer_interact_pos()
+		.is_some_and(|pos| axis(pos) > center)
+}
+
+fn selection_bar(ui: &mut Ui, hed: &mut Hed) {
+	ui.horizontal_wrapped(|ui| {
+		ui.label(tf("options.selected", &[&hed.selection.len()]));
+		if ui.button(t("options.enable")).clicked() {
+			hed.send_event(Event::ToggleHostsEnable(
+				hed.selected_host_keys(),
+				true,
+			));
+		}
+		if ui.button(t("options.disable")).clicked() {
+			hed.send_event(Event::ToggleHostsEnable(
+				hed.selected_host_keys(),
+				false,
+			));
+		}
+		if ui.button(t("options.delete")).clicked() {
+			hed.send_event(Event::DeleteHosts(hed.selected_host_keys()));
+		}
+		if ui.button(t("options.move_to")).clicked() {
+			hed.set_opened_window(OpenedWindow::MoveHosts);
+		}
+		if ui.button(t("options.copy_to")).clicked() {
+			hed.set_opened_window(OpenedWindow::CopyHosts);
+		}
+		if ui.button(t("options.clear")).clicked() {
+			hed.selection.clear();
+		}
+	});
 }
 
 fn text_view(ui: &mut Ui, hed: &mut Hed) {
 	ui.horizontal(|ui| {
 		ui.set_height(30.0);
-		if ui
-			.add(Button::new("Fromat").shortcut_text(
-				ui.ctx().format_shortcut(&format_btn_shortcut()),
-			))
-			.clicked()
+		if command_button(
+			ui,
+			hed,
+			t("text.format"),
+			Command::Format,
+			true,
+			false,
+		)
+		.clicked()
 		{
 			hed.update_content();
 		}
+		if command_button(
+			ui,
+			hed,
+			t("text.normalize"),
+			Command::Normalize,
+			hed.loaded,
+			false,
+		)
+		.clicked()
+		{
+			hed.open_normalize_window();
+		}
+		if command_button(
+			ui,
+			hed,
+			t("options.replace"),
+			Command::FindReplace,
+			true,
+			true,
+		)
+		.clicked()
+		{
+			hed.open_find_replace_window(ReplaceTarget::Text);
+		}
 	});
 
 	ui.separator();
@@ -198,28 +865,18 @@ fn text_view(ui: &mut Ui, hed: &mut Hed) {
 						.code_editor()
 						.show(ui);
 
-				if output.response.has_focus() {
-					if ui
-						.input_mut(|i| i.consume_shortcut(&save_btn_shortcut()))
-					{
-						hed.save_hosts();
-					}
-
-					if ui.input_mut(|i| {
-						i.consume_shortcut(&reset_btn_shortcut())
-					}) {
-						hed.reset_hosts();
-					}
-
-					if ui.input_mut(|i| {
-						i.consume_shortcut(&format_btn_shortcut())
-					}) {
-						hed.update_content();
-					}
-				}
+				a11y_label(
+					&output.response,
+					WidgetType::TextEdit,
+					t("a11y.hosts_content"),
+				);
 
 				if output.response.changed() {
-					hed.update_list();
+					hed.edit_content();
+				}
+				// line numbers of the lints are stale until the list updates
+				if !hed.is_content_edit_pending() {
+					text_lint_markers(ui, hed, &output);
 				}
 			});
 		});
@@ -1,15 +1,12 @@
-use egui::{Align, Button, Context, Layout, TopBottomPanel, Ui};
+use egui::{Align, Context, Layout, TopBottomPanel, Ui};
 
-use super::common::{
-	reset_btn_shortcut, save_btn_shortcut, set_button_padding,
+use super::common::{command_button, set_button_padding};
+use crate::{
+	core::{Command, Hed, ViewKind},
+	i18n::t,
 };
-use crate::core::{Hed, ViewKind};
 
 pub fn editor_header(ctx: &Context, hed: &mut Hed) {
-	if !hed.os_err.is_empty() {
-		return;
-	}
-
 	TopBottomPanel::top("editor_header")
 		.exact_height(40.0)
 		.show(ctx, |ui| {
@@ -29,51 +26,110 @@ fn panel_content(ui: &mut Ui, hed: &mut Hed) {
 	ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
 		set_button_padding(ui);
 
+		file_menu(ui, hed);
+		ui.separator();
+
 		if panel_width < 1200.0 {
 			hed.view_all = false;
-			ui.selectable_value(
-				&mut hed.view_kind,
+			let options_view = ui.selectable_value(
+				&mut hed.settings.view_kind,
 				ViewKind::Options,
-				"Options View",
+				t("view.options"),
 			);
 			ui.heading("/");
-			ui.selectable_value(
-				&mut hed.view_kind,
+			let text_view = ui.selectable_value(
+				&mut hed.settings.view_kind,
 				ViewKind::Text,
-				"Text View",
+				t("view.text"),
 			);
+			if options_view.changed() || text_view.changed() {
+				hed.save_settings();
+			}
 		} else {
 			hed.view_all = true;
 		}
 
 		ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-			if ui
-				.add_enabled(
-					changed,
-					Button::new("Reset").shortcut_text(
-						ui.ctx().format_shortcut(&reset_btn_shortcut()),
-					),
-				)
-				.clicked()
+			if command_button(
+				ui,
+				hed,
+				t("command.reset"),
+				Command::Reset,
+				changed,
+				false,
+			)
+			.clicked()
 			{
 				hed.reset_hosts();
 			}
 
-			if ui
-				.add_enabled(
-					changed,
-					Button::new("Save").shortcut_text(
-						ui.ctx().format_shortcut(&save_btn_shortcut()),
-					),
-				)
-				.clicked()
+			if command_button(
+				ui,
+				hed,
+				t("command.save"),
+				Command::Save,
+				hed.can_save(),
+				false,
+			)
+			.clicked()
 			{
 				hed.save_hosts();
 			};
 
-			if ui.button("↺").clicked() {
-				hed.parse_sys_hosts();
+			if command_button(ui, hed, "↺", Command::Reload, true, true)
+				.clicked()
+			{
+				hed.reload_hosts();
+			}
+
+			if command_button(ui, hed, "⛭", Command::OpenSettings, true, true)
+				.clicked()
+			{
+				hed.open_settings_window();
+			}
+
+			if hed.loaded {
+				hed.refresh_audit();
+			}
+			let audit = if hed.findings.is_empty() {
+				"🛡".to_string()
+			} else {
+				format!("🛡 {}", hed.findings.len())
+			};
+			if command_button(ui, hed, &audit, Command::Audit, hed.loaded, true)
+				.clicked()
+			{
+				hed.open_audit_window();
+			}
+
+			if hed.read_only {
+				ui.label(t("header.read_only"));
 			}
 		});
 	});
 }
+
+fn file_menu(ui: &mut Ui, hed: &mut Hed) {
+	ui.menu_button(t("menu.file"), |ui| {
+		let items = [
+			(Command::OpenFile, true),
+			(Command::SaveAs, hed.loaded),
+			(Command::CloseTab, hed.active_tab != 0),
+		];
+		for (command, enabled) in items {
+			if command_button(
+				ui,
+				hed,
+				command.label(),
+				command.clone(),
+				enabled,
+				false,
+			)
+			.clicked()
+			{
+				ui.close_menu();
+				hed.run_command(command);
+			}
+		}
+	});
+}
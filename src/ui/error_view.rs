@@ -0,0 +1,69 @@
+use egui::{Align, Context, Layout, RichText, TopBottomPanel, Ui};
+
+use super::common::set_button_padding;
+use crate::{
+	core::{Hed, HostsError},
+	i18n::t,
+};
+
+/// The error of a loaded hosts file, above the editor so the draft stays
+/// in reach.
+pub fn error_bar(ctx: &Context, hed: &mut Hed) {
+	if !hed.loaded {
+		return;
+	}
+	let Some(error) = hed.error.clone() else {
+		return;
+	};
+
+	TopBottomPanel::top("error_bar").show(ctx, |ui| {
+		ui.add_enabled_ui(hed.opened_window.is_none(), |ui| {
+			ui.with_layout(Layout::left_to_right(Align::Center), |ui| {
+				set_button_padding(ui);
+				error_text(ui, &error);
+				ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+					if ui.button(t("recovery.dismiss")).clicked() {
+						hed.dismiss_error();
+					}
+					for recovery in hed.recoveries(&error).into_iter().rev() {
+						if ui.button(recovery.label()).clicked() {
+							hed.recover(recovery);
+						}
+					}
+				});
+			});
+		});
+	});
+}
+
+/// The error of a hosts file that could not be read, in place of the editor.
+pub fn error_screen(ui: &mut Ui, hed: &mut Hed) {
+	let Some(error) = hed.error.clone() else {
+		return;
+	};
+
+	ui.vertical_centered(|ui| {
+		ui.add_space(ui.available_height() / 3.0);
+		ui.heading(error.title());
+		if !error.detail().is_empty() {
+			ui.label(error.detail());
+		}
+		ui.add_space(12.0);
+		ui.horizontal_wrapped(|ui| {
+			set_button_padding(ui);
+			for recovery in hed.recoveries(&error) {
+				if ui.button(recovery.label()).clicked() {
+					hed.recover(recovery);
+				}
+			}
+		});
+	});
+}
+
+fn error_text(ui: &mut Ui, error: &HostsError) {
+	let color = ui.visuals().error_fg_color;
+	ui.label(RichText::new(error.title()).strong().color(color));
+	if !error.detail().is_empty() {
+		ui.label(error.detail());
+	}
+}
@@ -0,0 +1,58 @@
+use egui::{Context, TopBottomPanel, Ui, WidgetType};
+
+use super::common::a11y_label;
+use crate::{core::Hed, i18n::t};
+
+/// One tab per open file, shown once more than the system hosts file is
+/// open.
+pub fn file_tabs(ctx: &Context, hed: &mut Hed) {
+	if hed.tab_count() < 2 {
+		return;
+	}
+
+	TopBottomPanel::top("file_tabs").show(ctx, |ui| {
+		ui.add_enabled_ui(
+			hed.pending_discard.is_none() && hed.opened_window.is_none(),
+			|ui| {
+				ui.horizontal_wrapped(|ui| {
+					for idx in 0..hed.tab_count() {
+						tab_ui(ui, hed, idx);
+					}
+				});
+			},
+		);
+	});
+}
+
+fn tab_ui(ui: &mut Ui, hed: &mut Hed, idx: usize) {
+	let (path, is_system, dirty) = hed.tab_info(idx);
+	let name = if is_system {
+		t("tab.system").to_string()
+	} else {
+		path.file_name().map_or_else(
+			|| path.display().to_string(),
+			|name| name.to_string_lossy().into_owned(),
+		)
+	};
+	let text = if dirty {
+		format!("● {}", name)
+	} else {
+		name
+	};
+	let path = path.display().to_string();
+
+	let tab = ui
+		.selectable_label(idx == hed.active_tab, text)
+		.on_hover_text(path);
+	if tab.clicked() {
+		hed.switch_tab(idx);
+	}
+	if !is_system {
+		let close = ui.small_button("✕").on_hover_text(t("command.close_tab"));
+		a11y_label(&close, WidgetType::Button, t("command.close_tab"));
+		if close.clicked() {
+			hed.close_tab(idx);
+		}
+	}
+	ui.separator();
+}
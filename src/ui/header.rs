@@ -2,16 +2,17 @@
 use egui::Window;
 use egui::{
 	special_emojis, Align, Context, FontId, Layout, RichText, TopBottomPanel,
-	Ui, Visuals,
+	Ui, WidgetType,
 };
 
-use super::common::set_button_padding;
+use super::common::{a11y_label, set_button_padding};
 use crate::{
 	consts::{APP_NAME, APP_REPOSITORY, APP_VER},
-	core::Hed,
+	core::{Hed, Theme},
+	i18n::{t, tf},
 };
 
-pub fn header(ctx: &Context, _hed: &mut Hed) {
+pub fn header(ctx: &Context, hed: &mut Hed) {
 	TopBottomPanel::top("header")
 		.exact_height(48.0)
 		.show(ctx, |ui| {
@@ -22,9 +23,9 @@ pub fn header(ctx: &Context, _hed: &mut Hed) {
 				);
 				ui.hyperlink_to(
 					RichText::new(format!(
-						"{} {} on GitHub",
+						"{} {}",
 						special_emojis::GITHUB,
-						APP_NAME
+						tf("header.github", &[&APP_NAME])
 					))
 					.font(FontId::proportional(14.0)),
 					APP_REPOSITORY,
@@ -33,9 +34,21 @@ pub fn header(ctx: &Context, _hed: &mut Hed) {
 					set_button_padding(ui);
 
 					#[cfg(feature = "_dev")]
-					dev_window(ctx, ui, _hed);
+					dev_window(ctx, ui, hed);
 
-					theme_switch(ctx, ui);
+					theme_switch(ui, hed);
+
+					let history = ui
+						.selectable_label(hed.toasts.history_open, "🔔")
+						.on_hover_text(t("toast.history"));
+					a11y_label(
+						&history,
+						WidgetType::Button,
+						t("toast.history"),
+					);
+					if history.clicked() {
+						hed.toasts.history_open = !hed.toasts.history_open;
+					}
 				});
 			});
 		});
@@ -54,13 +67,13 @@ fn dev_window(ctx: &Context, ui: &mut Ui, hed: &mut Hed) {
 		});
 }
 
-fn theme_switch(ctx: &Context, ui: &mut Ui) {
-	let dark_mode = ctx.style().visuals.dark_mode;
-	if ui.selectable_label(!dark_mode, "☀ Light").clicked() {
-		ctx.set_visuals(Visuals::light());
+fn theme_switch(ui: &mut Ui, hed: &mut Hed) {
+	let dark_mode = hed.settings.theme == Theme::Dark;
+	if ui.selectable_label(!dark_mode, t("header.light")).clicked() {
+		hed.set_theme(Theme::Light);
 	}
-	if ui.selectable_label(dark_mode, "🌙 Dark").clicked() {
-		ctx.set_visuals(Visuals::dark());
+	if ui.selectable_label(dark_mode, t("header.dark")).clicked() {
+		hed.set_theme(Theme::Dark);
 	}
 }
 
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use egui::{
+	text_edit::TextEditOutput, vec2, Color32, Rect, RichText, Ui, WidgetType,
+};
+
+use super::common::{a11y_label, set_button_padding};
+use crate::{
+	core::{Event, Hed, Lint, LintLevel},
+	i18n::{t, tf},
+};
+
+/// Lints of the draft by the ip of their line.
+pub type LintsByIp<'a> = HashMap<&'a str, Vec<&'a Lint>>;
+
+pub fn lints_by_ip(lints: &[Lint]) -> LintsByIp<'_> {
+	let mut map: LintsByIp = HashMap::new();
+	for lint in lints {
+		if let Some(ip) = &lint.ip {
+			map.entry(ip).or_default().push(lint);
+		}
+	}
+	map
+}
+
+/// An icon of the worst of `lints`, opening their messages and fixes.
+pub fn lint_menu(ui: &mut Ui, hed: &Hed, lints: &[&Lint]) {
+	let Some(level) = lints.iter().map(|lint| lint.rule.level()).max() else {
+		return;
+	};
+	let messages: Vec<String> =
+		lints.iter().map(|lint| lint.rule.message()).collect();
+	let (icon, color) = level_icon(ui, level);
+
+	let menu = ui.menu_button(RichText::new(icon).color(color), |ui| {
+		set_button_padding(ui);
+		for lint in lints {
+			lint_row(ui, hed, lint);
+		}
+	});
+	let response = menu.response.on_hover_text(messages.join("\n"));
+	a11y_label(
+		&response,
+		WidgetType::Button,
+		&tf("a11y.lints", &[&messages.join(", ")]),
+	);
+}
+
+/// Lints of the whole file rather than of a line.
+pub fn lint_bar(ui: &mut Ui, hed: &Hed) {
+	for lint in hed.lints.iter().filter(|lint| lint.line.is_none()) {
+		ui.horizontal(|ui| {
+			lint_row(ui, hed, lint);
+		});
+	}
+}
+
+fn lint_row(ui: &mut Ui, hed: &Hed, lint: &Lint) {
+	let (icon, color) = level_icon(ui, lint.rule.level());
+	ui.horizontal(|ui| {
+		ui.label(RichText::new(icon).color(color));
+		ui.label(lint.rule.message());
+		if lint.fix.is_some() && ui.small_button(t("lint.fix")).clicked() {
+			hed.send_event(Event::FixLint(lint.clone()));
+			ui.close_menu();
+		}
+	});
+}
+
+/// A lint menu at the end of the first row of each line with lints.
+pub fn text_lint_markers(ui: &mut Ui, hed: &Hed, output: &TextEditOutput) {
+	let mut by_line: HashMap<usize, Vec<&Lint>> = HashMap::new();
+	for lint in &hed.lints {
+		if let Some(line) = lint.line {
+			by_line.entry(line).or_default().push(lint);
+		}
+	}
+	if by_line.is_empty() {
+		return;
+	}
+
+	let right = output.response.rect.right();
+	let mut line = 0;
+	let mut line_start = true;
+	for row in &output.galley.rows {
+		if line_start {
+			if let Some(lints) = by_line.get(&line) {
+				let rect = row.rect.translate(output.galley_pos.to_vec2());
+				let marker = Rect::from_min_size(
+					egui::pos2(right - 24.0, rect.top()),
+					vec2(24.0, rect.height()),
+				);
+				let color = level_icon(
+					ui,
+					lints.iter().map(|lint| lint.rule.level()).max().unwrap(),
+				)
+				.1;
+				ui.painter().hline(
+					rect.x_range(),
+					rect.bottom(),
+					(1.0, color.gamma_multiply(0.6)),
+				);
+				ui.allocate_ui_at_rect(marker, |ui| {
+					ui.spacing_mut().button_padding = vec2(2.0, 0.0);
+					lint_menu(ui, hed, lints);
+				});
+			}
+		}
+		line_start = row.ends_with_newline;
+		if row.ends_with_newline {
+			line += 1;
+		}
+	}
+}
+
+fn level_icon(ui: &Ui, level: LintLevel) -> (&'static str, Color32) {
+	match level {
+		LintLevel::Info => ("ℹ", ui.visuals().text_color()),
+		LintLevel::Warning => ("⚠", ui.visuals().warn_fg_color),
+		LintLevel::Error => ("✖", ui.visuals().error_fg_color),
+	}
+}
@@ -1,10 +1,20 @@
 mod all_window;
+mod command_palette;
 mod common;
 mod component;
 mod editor;
 mod editor_header;
+mod error_view;
+mod file_tabs;
 mod header;
+mod lint_view;
+mod toasts;
 
+pub use command_palette::command_palette;
+pub use common::{handle_shortcuts, set_fonts, set_style};
 pub use editor::editor;
 pub use editor_header::editor_header;
+pub use error_view::error_bar;
+pub use file_tabs::file_tabs;
 pub use header::header;
+pub use toasts::toasts;
@@ -0,0 +1,143 @@
+use egui::{
+	Align2, Area, Button, Color32, Context, Frame, Id, Label, Order, RichText,
+	ScrollArea, Ui, Window,
+};
+
+use super::component::SUCCESS_COLOR;
+use crate::{
+	core::{Hed, Toast, ToastAction, ToastLevel},
+	i18n::t,
+	util::format_local_time,
+};
+
+const TOAST_WIDTH: f32 = 320.0;
+
+/// Toasts stacked at the bottom right, over everything else.
+pub fn toasts(ctx: &Context, hed: &mut Hed) {
+	history_window(ctx, hed);
+
+	if hed.toasts.active.is_empty() {
+		return;
+	}
+
+	let active = hed.toasts.active.clone();
+	Area::new(Id::new("toasts"))
+		.anchor(Align2::RIGHT_BOTTOM, [-12.0, -12.0])
+		.order(Order::Foreground)
+		.show(ctx, |ui| {
+			ui.set_width(TOAST_WIDTH);
+			for toast in &active {
+				let resp = Frame::popup(ui.style())
+					.show(ui, |ui| {
+						toast_ui(ui, hed, toast);
+					})
+					.response;
+				if resp.contains_pointer() {
+					hed.toasts.hold(toast.id);
+				}
+			}
+		});
+}
+
+fn toast_ui(ui: &mut Ui, hed: &mut Hed, toast: &Toast) {
+	ui.set_width(TOAST_WIDTH);
+	ui.horizontal(|ui| {
+		ui.label(
+			RichText::new(level_icon(toast.level))
+				.color(level_color(ui, toast.level)),
+		);
+		ui.add(Label::new(&toast.message).wrap());
+	});
+	ui.horizontal(|ui| {
+		for action in &toast.actions {
+			let enabled = match action {
+				ToastAction::Undo => toast
+					.change
+					.as_deref()
+					.is_some_and(|change| hed.can_undo_save(change)),
+				ToastAction::ShowDiff | ToastAction::ShowAudit => true,
+			};
+			if ui
+				.add_enabled(enabled, Button::new(action_label(*action)))
+				.clicked()
+			{
+				hed.run_toast_action(toast.id, *action);
+			}
+		}
+		if ui.small_button(t("recovery.dismiss")).clicked() {
+			hed.toasts.dismiss(toast.id);
+		}
+	});
+}
+
+fn history_window(ctx: &Context, hed: &mut Hed) {
+	if !hed.toasts.history_open {
+		return;
+	}
+
+	let history = hed.toasts.history.clone();
+	let mut open = true;
+	Window::new(t("toast.history"))
+		.open(&mut open)
+		.anchor(Align2::RIGHT_TOP, [-12.0, 60.0])
+		.collapsible(false)
+		.resizable(false)
+		.show(ctx, |ui| {
+			if history.is_empty() {
+				ui.label(t("toast.no_history"));
+				return;
+			}
+			ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+				ui.set_width(TOAST_WIDTH);
+				for toast in &history {
+					ui.horizontal(|ui| {
+						ui.label(
+							RichText::new(level_icon(toast.level))
+								.color(level_color(ui, toast.level)),
+						);
+						ui.label(
+							RichText::new(format_local_time(toast.created_at))
+								.weak(),
+						);
+					});
+					ui.add(Label::new(&toast.message).wrap());
+					if toast.actions.contains(&ToastAction::ShowDiff)
+						&& ui
+							.small_button(action_label(ToastAction::ShowDiff))
+							.clicked()
+					{
+						hed.run_toast_action(toast.id, ToastAction::ShowDiff);
+					}
+					ui.separator();
+				}
+			});
+		});
+
+	hed.toasts.history_open = open;
+}
+
+fn level_icon(level: ToastLevel) -> &'static str {
+	match level {
+		ToastLevel::Success => "✔",
+		ToastLevel::Info => "ℹ",
+		ToastLevel::Warning => "⚠",
+		ToastLevel::Error => "✖",
+	}
+}
+
+fn level_color(ui: &Ui, level: ToastLevel) -> Color32 {
+	match level {
+		ToastLevel::Success => SUCCESS_COLOR,
+		ToastLevel::Info => ui.visuals().text_color(),
+		ToastLevel::Warning => ui.visuals().warn_fg_color,
+		ToastLevel::Error => ui.visuals().error_fg_color,
+	}
+}
+
+fn action_label(action: ToastAction) -> &'static str {
+	match action {
+		ToastAction::Undo => t("toast.undo"),
+		ToastAction::ShowDiff => t("toast.show_diff"),
+		ToastAction::ShowAudit => t("toast.show_audit"),
+	}
+}
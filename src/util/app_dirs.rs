@@ -0,0 +1,79 @@
+use std::{env, path::PathBuf};
+
+use anyhow::Result;
+
+use crate::consts::APP_NAME;
+
+#[cfg(all(not(feature = "_dev"), target_os = "windows"))]
+pub fn get_config_dir() -> Result<PathBuf> {
+	let app_data = env::var("APPDATA")?;
+	let path = PathBuf::from(app_data).join(APP_NAME);
+
+	Ok(path)
+}
+
+#[cfg(all(not(feature = "_dev"), target_os = "macos"))]
+pub fn get_config_dir() -> Result<PathBuf> {
+	let home = env::var("HOME")?;
+	let path = PathBuf::from(home)
+		.join("Library/Application Support")
+		.join(APP_NAME);
+
+	Ok(path)
+}
+
+#[cfg(all(
+	not(feature = "_dev"),
+	not(any(target_os = "windows", target_os = "macos"))
+))]
+pub fn get_config_dir() -> Result<PathBuf> {
+	let path = match env::var("XDG_CONFIG_HOME") {
+		Ok(config_home) if !config_home.is_empty() => {
+			PathBuf::from(config_home)
+		}
+		_ => PathBuf::from(env::var("HOME")?).join(".config"),
+	};
+
+	Ok(path.join(APP_NAME))
+}
+
+#[cfg(feature = "_dev")]
+pub fn get_config_dir() -> Result<PathBuf> {
+	let mut path = env::current_dir()?;
+
+	path.push("tmp");
+	path.push(APP_NAME);
+
+	Ok(path)
+}
+
+#[cfg(all(not(feature = "_dev"), target_os = "windows"))]
+pub fn get_data_dir() -> Result<PathBuf> {
+	let local_app_data = env::var("LOCALAPPDATA")?;
+	let path = PathBuf::from(local_app_data).join(APP_NAME);
+
+	Ok(path)
+}
+
+#[cfg(all(not(feature = "_dev"), target_os = "macos"))]
+pub fn get_data_dir() -> Result<PathBuf> {
+	get_config_dir()
+}
+
+#[cfg(all(
+	not(feature = "_dev"),
+	not(any(target_os = "windows", target_os = "macos"))
+))]
+pub fn get_data_dir() -> Result<PathBuf> {
+	let path = match env::var("XDG_DATA_HOME") {
+		Ok(data_home) if !data_home.is_empty() => PathBuf::from(data_home),
+		_ => PathBuf::from(env::var("HOME")?).join(".local/share"),
+	};
+
+	Ok(path.join(APP_NAME))
+}
+
+#[cfg(feature = "_dev")]
+pub fn get_data_dir() -> Result<PathBuf> {
+	get_config_dir()
+}
@@ -0,0 +1,65 @@
+use std::{path::Path, process::Command};
+
+use anyhow::{bail, Result};
+
+use crate::i18n::t;
+
+/// Copy `from` over `to` as administrator, the system asks the user to
+/// allow it. Copying keeps the owner and permissions of an existing `to`.
+pub fn copy_elevated(from: &Path, to: &Path) -> Result<()> {
+	let status = elevated_copy_command(from, to).status()?;
+	if !status.success() {
+		bail!(t("error.elevation_failed"));
+	}
+
+	Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn elevated_copy_command(from: &Path, to: &Path) -> Command {
+	use std::os::windows::process::CommandExt;
+
+	const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+	let quote =
+		|path: &Path| format!("\"{}\"", path.display()).replace('\'', "''");
+	let script = format!(
+		"$p = Start-Process -FilePath cmd.exe -ArgumentList \
+		 '/c copy /y {} {}' -Verb RunAs -WindowStyle Hidden -Wait \
+		 -PassThru; exit $p.ExitCode",
+		quote(from),
+		quote(to)
+	);
+
+	let mut command = Command::new("powershell");
+	command
+		.args(["-NoProfile", "-NonInteractive", "-Command", &script])
+		.creation_flags(CREATE_NO_WINDOW);
+
+	command
+}
+
+#[cfg(target_os = "macos")]
+fn elevated_copy_command(from: &Path, to: &Path) -> Command {
+	let quote = |path: &Path| {
+		format!("'{}'", path.display().to_string().replace('\'', "'\\''"))
+	};
+	let shell = format!("/bin/cp {} {}", quote(from), quote(to));
+	let script = format!(
+		"do shell script \"{}\" with administrator privileges",
+		shell.replace('\\', "\\\\").replace('"', "\\\"")
+	);
+
+	let mut command = Command::new("osascript");
+	command.args(["-e", &script]);
+
+	command
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn elevated_copy_command(from: &Path, to: &Path) -> Command {
+	let mut command = Command::new("pkexec");
+	command.arg("cp").arg("--").arg(from).arg(to);
+
+	command
+}
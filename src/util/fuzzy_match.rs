@@ -0,0 +1,55 @@
+/// Match `pattern` as a case-insensitive subsequence of `text`, returning a
+/// score that favours consecutive matches and matches at word starts.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<usize> {
+	let mut score = 0;
+	let mut pattern_chars = pattern
+		.chars()
+		.filter(|c| !c.is_whitespace())
+		.flat_map(char::to_lowercase)
+		.peekable();
+	let mut prev: Option<char> = None;
+	let mut prev_matched = false;
+
+	for c in text.chars().flat_map(char::to_lowercase) {
+		let Some(p) = pattern_chars.peek() else {
+			break;
+		};
+		if *p == c {
+			score += 1;
+			if prev_matched {
+				score += 2;
+			}
+			if prev.is_none_or(|prev| !prev.is_alphanumeric()) {
+				score += 3;
+			}
+			pattern_chars.next();
+			prev_matched = true;
+		} else {
+			prev_matched = false;
+		}
+		prev = Some(c);
+	}
+
+	if pattern_chars.peek().is_none() {
+		Some(score)
+	} else {
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::fuzzy_match;
+
+	#[test]
+	fn test_fuzzy_match() {
+		assert_eq!(fuzzy_match("", "Save"), Some(0));
+		assert!(fuzzy_match("sv", "Save").is_some());
+		assert!(fuzzy_match("vs", "Save").is_none());
+		assert!(
+			fuzzy_match("save", "Save hosts")
+				> fuzzy_match("save", "Switch to text view")
+		);
+		assert!(fuzzy_match("tv", "Text View") > fuzzy_match("tv", "Toggle"));
+	}
+}
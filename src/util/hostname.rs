@@ -0,0 +1,23 @@
+#[cfg(target_os = "windows")]
+pub fn get_hostname() -> Option<String> {
+	std::env::var("COMPUTERNAME")
+		.ok()
+		.filter(|name| !name.is_empty())
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn get_hostname() -> Option<String> {
+	extern "C" {
+		fn gethostname(name: *mut std::ffi::c_char, len: usize) -> i32;
+	}
+
+	let mut buf = [0u8; 256];
+	let result = unsafe { gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+	if result != 0 {
+		return None;
+	}
+	let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+	let name = String::from_utf8_lossy(&buf[..len]).into_owned();
+
+	(!name.is_empty()).then_some(name)
+}
@@ -1,9 +1,28 @@
+mod app_dirs;
+mod elevate;
+mod fuzzy_match;
 mod global_id;
+mod hostname;
 mod is_ip;
+mod os_locale;
 mod string_ext;
 mod sys_hosts_path;
+mod system_fonts;
+mod temp_file;
+mod time;
 
+pub use app_dirs::{get_config_dir, get_data_dir};
+pub use elevate::copy_elevated;
+pub use fuzzy_match::fuzzy_match;
 pub use global_id::GLOBAL_ID;
+pub use hostname::get_hostname;
 pub use is_ip::is_ip;
+pub use os_locale::get_os_locale;
 pub use string_ext::StringExt;
 pub use sys_hosts_path::get_sys_hosts_path;
+pub use system_fonts::get_fallback_fonts;
+pub use temp_file::TempFile;
+pub use time::{
+	format_countdown, format_local_time, now_secs, parse_duration,
+	parse_local_time,
+};
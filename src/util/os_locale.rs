@@ -0,0 +1,47 @@
+#[cfg(not(target_os = "windows"))]
+use std::env;
+
+#[cfg(target_os = "windows")]
+pub fn get_os_locale() -> Option<String> {
+	#[link(name = "kernel32")]
+	extern "system" {
+		fn GetUserDefaultLocaleName(name: *mut u16, len: i32) -> i32;
+	}
+
+	let mut buf = [0u16; 85];
+	let len =
+		unsafe { GetUserDefaultLocaleName(buf.as_mut_ptr(), buf.len() as i32) };
+	if len <= 1 {
+		return None;
+	}
+
+	Some(String::from_utf16_lossy(&buf[..len as usize - 1]))
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn get_os_locale() -> Option<String> {
+	let from_env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+		.into_iter()
+		.filter_map(|key| env::var(key).ok())
+		.find(|value| !value.is_empty());
+
+	if from_env.is_some() {
+		return from_env;
+	}
+
+	// GUI apps on macOS don't get a `LANG`, ask the user defaults instead
+	#[cfg(target_os = "macos")]
+	{
+		let output = std::process::Command::new("defaults")
+			.args(["read", "-g", "AppleLocale"])
+			.output()
+			.ok()?;
+		let locale = String::from_utf8(output.stdout).ok()?;
+		let locale = locale.trim();
+		if !locale.is_empty() {
+			return Some(locale.to_string());
+		}
+	}
+
+	None
+}
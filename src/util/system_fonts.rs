@@ -0,0 +1,171 @@
+use std::{
+	env, fs,
+	path::{Path, PathBuf},
+};
+
+/// How deep to look into font directories, fontconfig nests them by vendor.
+const MAX_DEPTH: usize = 4;
+
+/// Font files covering CJK and other non-latin scripts, in order of
+/// preference.
+#[cfg(target_os = "windows")]
+const FONT_FILES: &[&str] = &[
+	"msyh.ttc",
+	"msyh.ttf",
+	"simhei.ttf",
+	"simsun.ttc",
+	"msjh.ttc",
+	"meiryo.ttc",
+	"msgothic.ttc",
+	"malgun.ttf",
+	"seguisym.ttf",
+];
+
+#[cfg(target_os = "macos")]
+const FONT_FILES: &[&str] = &[
+	"PingFang.ttc",
+	"Hiragino Sans GB.ttc",
+	"STHeiti Light.ttc",
+	"STHeiti Medium.ttc",
+	"AppleSDGothicNeo.ttc",
+	"Arial Unicode.ttf",
+];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const FONT_FILES: &[&str] = &[
+	"NotoSansCJK-Regular.ttc",
+	"NotoSansCJKsc-Regular.otf",
+	"NotoSansSC-Regular.otf",
+	"SourceHanSansSC-Regular.otf",
+	"SourceHanSans-Regular.ttc",
+	"wqy-microhei.ttc",
+	"wqy-zenhei.ttc",
+	"DroidSansFallbackFull.ttf",
+	"DroidSansFallback.ttf",
+	"NotoSansSymbols2-Regular.ttf",
+];
+
+#[cfg(target_os = "windows")]
+fn font_dirs() -> Vec<PathBuf> {
+	let windir = env::var("WINDIR").unwrap_or_else(|_| "C:\\Windows".into());
+	let mut dirs = vec![PathBuf::from(windir).join("Fonts")];
+	if let Ok(local) = env::var("LOCALAPPDATA") {
+		dirs.push(PathBuf::from(local).join("Microsoft\\Windows\\Fonts"));
+	}
+
+	dirs
+}
+
+#[cfg(target_os = "macos")]
+fn font_dirs() -> Vec<PathBuf> {
+	let mut dirs = vec![
+		PathBuf::from("/System/Library/Fonts"),
+		PathBuf::from("/Library/Fonts"),
+	];
+	if let Ok(home) = env::var("HOME") {
+		dirs.push(PathBuf::from(home).join("Library/Fonts"));
+	}
+
+	dirs
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn font_dirs() -> Vec<PathBuf> {
+	let mut dirs = vec![];
+	match env::var("XDG_DATA_HOME") {
+		Ok(data_home) if !data_home.is_empty() => {
+			dirs.push(PathBuf::from(data_home).join("fonts"));
+		}
+		_ => {
+			if let Ok(home) = env::var("HOME") {
+				dirs.push(PathBuf::from(&home).join(".local/share/fonts"));
+			}
+		}
+	}
+	if let Ok(home) = env::var("HOME") {
+		dirs.push(PathBuf::from(home).join(".fonts"));
+	}
+	let data_dirs = env::var("XDG_DATA_DIRS")
+		.ok()
+		.filter(|dirs| !dirs.is_empty())
+		.unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+	for dir in data_dirs.split(':').filter(|dir| !dir.is_empty()) {
+		dirs.push(PathBuf::from(dir).join("fonts"));
+	}
+
+	dirs
+}
+
+/// System fonts to register as fallbacks, in order of preference.
+pub fn get_fallback_fonts() -> Vec<PathBuf> {
+	find_font_files(&font_dirs(), FONT_FILES)
+}
+
+fn find_font_files(dirs: &[PathBuf], names: &[&str]) -> Vec<PathBuf> {
+	let mut found: Vec<Option<PathBuf>> = vec![None; names.len()];
+
+	for dir in dirs {
+		visit(dir, MAX_DEPTH, &mut |path| {
+			let Some(file_name) = path.file_name() else {
+				return;
+			};
+			let Some(idx) = names
+				.iter()
+				.position(|name| file_name.eq_ignore_ascii_case(name))
+			else {
+				return;
+			};
+			found[idx].get_or_insert_with(|| path.to_path_buf());
+		});
+	}
+
+	found.into_iter().flatten().collect()
+}
+
+fn visit(dir: &Path, depth: usize, f: &mut impl FnMut(&Path)) {
+	let Ok(entries) = fs::read_dir(dir) else {
+		return;
+	};
+
+	for entry in entries.flatten() {
+		let path = entry.path();
+		if path.is_dir() {
+			if depth > 0 {
+				visit(&path, depth - 1, f);
+			}
+		} else {
+			f(&path);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{env, fs};
+
+	use super::find_font_files;
+
+	#[test]
+	fn test_find_font_files() {
+		let root = env::temp_dir().join("hed_test_find_font_files");
+		let _ = fs::remove_dir_all(&root);
+		fs::create_dir_all(root.join("a/b")).unwrap();
+		fs::create_dir_all(root.join("c")).unwrap();
+		fs::write(root.join("a/b/second.ttf"), "").unwrap();
+		fs::write(root.join("c/FIRST.ttc"), "").unwrap();
+		fs::write(root.join("c/second.ttf"), "").unwrap();
+		fs::write(root.join("c/other.ttf"), "").unwrap();
+
+		let found = find_font_files(
+			&[root.join("a"), root.join("c"), root.join("missing")],
+			&["first.ttc", "second.ttf", "third.otf"],
+		);
+
+		assert_eq!(
+			found,
+			vec![root.join("c/FIRST.ttc"), root.join("a/b/second.ttf")]
+		);
+
+		fs::remove_dir_all(&root).unwrap();
+	}
+}
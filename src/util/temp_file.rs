@@ -0,0 +1,91 @@
+use std::{
+	fs::{self, File, OpenOptions},
+	io::{self, Write},
+	path::{Path, PathBuf},
+	process,
+	sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+use anyhow::Result;
+
+use super::{get_data_dir, now_secs};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A new file in a directory only the current user can write, so that no
+/// one else can swap it, removed again when dropped.
+pub struct TempFile {
+	path: PathBuf,
+	file: File,
+}
+
+impl TempFile {
+	pub fn new() -> Result<Self> {
+		Self::new_in(&get_data_dir()?.join("tmp"))
+	}
+
+	fn new_in(dir: &Path) -> Result<Self> {
+		fs::create_dir_all(dir)?;
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::PermissionsExt;
+			fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+		}
+
+		loop {
+			let name = format!(
+				"hed_{}_{}_{}",
+				process::id(),
+				now_secs(),
+				COUNTER.fetch_add(1, Relaxed)
+			);
+			let path = dir.join(name);
+			match OpenOptions::new().write(true).create_new(true).open(&path) {
+				Ok(file) => return Ok(Self { path, file }),
+				Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+				Err(err) => return Err(err.into()),
+			}
+		}
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	pub fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		self.file.write_all(bytes)?;
+		self.file.sync_all()?;
+
+		Ok(())
+	}
+}
+
+impl Drop for TempFile {
+	fn drop(&mut self) {
+		let _ = fs::remove_file(&self.path);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{env, fs};
+
+	use super::TempFile;
+
+	#[test]
+	fn test_temp_file() {
+		let dir = env::temp_dir().join("hed_test_temp_file");
+		let mut first = TempFile::new_in(&dir).unwrap();
+		let second = TempFile::new_in(&dir).unwrap();
+		assert_ne!(first.path(), second.path());
+
+		first.write_all(b"127.0.0.1 a.test\n").unwrap();
+		let path = first.path().to_path_buf();
+		assert_eq!(fs::read_to_string(&path).unwrap(), "127.0.0.1 a.test\n");
+		drop(first);
+		assert!(!path.exists());
+
+		drop(second);
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
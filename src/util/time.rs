@@ -0,0 +1,226 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{Days, Local, NaiveDate, NaiveTime, TimeZone};
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+pub fn now_secs() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_secs())
+		.unwrap_or_default()
+}
+
+/// Parse a duration like `30m`, `2h`, `1h30m` or `1d`, a bare number is
+/// minutes.
+pub fn parse_duration(s: &str) -> Option<u64> {
+	let s = s.trim().to_ascii_lowercase();
+	if s.is_empty() {
+		return None;
+	}
+	if let Ok(minutes) = s.parse::<u64>() {
+		return Some(minutes * MINUTE);
+	}
+
+	let mut total = 0;
+	let mut num = String::new();
+	for c in s.chars().filter(|c| !c.is_whitespace()) {
+		if c.is_ascii_digit() {
+			num.push(c);
+			continue;
+		}
+		let unit = match c {
+			'd' => DAY,
+			'h' => HOUR,
+			'm' => MINUTE,
+			's' => 1,
+			_ => return None,
+		};
+		total += num.parse::<u64>().ok()? * unit;
+		num.clear();
+	}
+
+	(num.is_empty() && total > 0).then_some(total)
+}
+
+/// Parse a local time, `HH:MM` is its next occurrence after `now`.
+pub fn parse_local_time(s: &str, now: u64) -> Option<u64> {
+	parse_time_in(s, now, &Local)
+}
+
+pub fn format_local_time(ts: u64) -> String {
+	format_time_in(ts, &Local)
+}
+
+/// Remaining time in its two largest units, e.g. `1h 05m` or `45s`.
+pub fn format_countdown(secs: u64) -> String {
+	if secs >= DAY {
+		format!("{}d {}h", secs / DAY, secs % DAY / HOUR)
+	} else if secs >= HOUR {
+		format!("{}h {:02}m", secs / HOUR, secs % HOUR / MINUTE)
+	} else if secs >= MINUTE {
+		format!("{}m {:02}s", secs / MINUTE, secs % MINUTE)
+	} else {
+		format!("{}s", secs)
+	}
+}
+
+/// The offset of `tz` is the one at the parsed time, which differs from the
+/// current one across a daylight saving change.
+fn parse_time_in<Tz: TimeZone>(s: &str, now: u64, tz: &Tz) -> Option<u64> {
+	let s = s.trim();
+	let (date, time) = match s.split_once([' ', 'T']) {
+		Some((date, time)) => (Some(date.trim()), time.trim()),
+		None => (None, s),
+	};
+	let time = NaiveTime::parse_from_str(time, "%H:%M").ok()?;
+	let to_ts = |date: NaiveDate| {
+		// the earlier of a repeated time, none for a skipped one
+		let local = tz.from_local_datetime(&date.and_time(time)).earliest()?;
+		u64::try_from(local.timestamp()).ok()
+	};
+
+	if let Some(date) = date {
+		return to_ts(NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?);
+	}
+	let today = tz.timestamp_opt(i64::try_from(now).ok()?, 0).single()?;
+	let today = today.date_naive();
+	match to_ts(today) {
+		Some(ts) if ts > now => Some(ts),
+		_ => to_ts(today.checked_add_days(Days::new(1))?),
+	}
+}
+
+fn format_time_in<Tz: TimeZone>(ts: u64, tz: &Tz) -> String
+where
+	Tz::Offset: std::fmt::Display,
+{
+	i64::try_from(ts)
+		.ok()
+		.and_then(|ts| tz.timestamp_opt(ts, 0).single())
+		.map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use chrono::{
+		FixedOffset, LocalResult, NaiveDate, NaiveDateTime, TimeZone,
+	};
+
+	use super::{
+		format_countdown, format_time_in, parse_duration, parse_time_in,
+	};
+
+	// 2024-03-10 15:20:00 UTC
+	const NOW: u64 = 1710084000;
+
+	#[test]
+	fn test_parse_duration() {
+		assert_eq!(parse_duration("30"), Some(30 * 60));
+		assert_eq!(parse_duration("1h30m"), Some(90 * 60));
+		assert_eq!(parse_duration("2H 5s"), Some(2 * 3600 + 5));
+		assert_eq!(parse_duration("1d"), Some(86400));
+		assert_eq!(parse_duration("0m"), None);
+		assert_eq!(parse_duration("10x"), None);
+		assert_eq!(parse_duration("1h30"), None);
+	}
+
+	#[test]
+	fn test_parse_time() {
+		let tz = FixedOffset::east_opt(8 * 3600).unwrap();
+		let utc = FixedOffset::east_opt(0).unwrap();
+		// 23:20 local
+		assert_eq!(format_time_in(NOW, &tz), "2024-03-10 23:20");
+		assert_eq!(parse_time_in("23:50", NOW, &tz), Some(NOW + 30 * 60));
+		assert_eq!(
+			parse_time_in("08:00", NOW, &tz),
+			Some(NOW + 8 * 3600 + 40 * 60)
+		);
+		assert_eq!(
+			parse_time_in("2024-03-11 08:00", NOW, &tz),
+			Some(NOW + 8 * 3600 + 40 * 60)
+		);
+		assert_eq!(
+			parse_time_in("2024-02-29 00:00", NOW, &utc)
+				.map(|ts| format_time_in(ts, &utc)),
+			Some("2024-02-29 00:00".to_string())
+		);
+		assert_eq!(parse_time_in("24:00", NOW, &tz), None);
+		assert_eq!(parse_time_in("2024-13-01 08:00", NOW, &utc), None);
+	}
+
+	/// UTC+1 that moves to UTC+2 at `NOW` plus a day.
+	#[derive(Clone)]
+	struct Dst;
+
+	const SWITCH: i64 = NOW as i64 + 86400;
+
+	impl TimeZone for Dst {
+		type Offset = FixedOffset;
+
+		fn from_offset(_: &FixedOffset) -> Self {
+			Dst
+		}
+
+		fn offset_from_local_date(
+			&self,
+			_: &NaiveDate,
+		) -> LocalResult<FixedOffset> {
+			unimplemented!()
+		}
+
+		fn offset_from_local_datetime(
+			&self,
+			local: &NaiveDateTime,
+		) -> LocalResult<FixedOffset> {
+			let local = local.and_utc().timestamp();
+			// +1 is valid for times before the switch, +2 for ones after
+			let offsets: Vec<FixedOffset> = [3600, 7200]
+				.into_iter()
+				.filter(|offset| {
+					(local - i64::from(*offset) < SWITCH) == (*offset == 3600)
+				})
+				.filter_map(FixedOffset::east_opt)
+				.collect();
+			match offsets[..] {
+				[offset] => LocalResult::Single(offset),
+				_ => LocalResult::None,
+			}
+		}
+
+		fn offset_from_utc_date(&self, _: &NaiveDate) -> FixedOffset {
+			unimplemented!()
+		}
+
+		fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+			let offset = if utc.and_utc().timestamp() < SWITCH {
+				3600
+			} else {
+				7200
+			};
+			FixedOffset::east_opt(offset).unwrap()
+		}
+	}
+
+	#[test]
+	fn test_parse_time_across_dst() {
+		// 2024-03-12 06:00 UTC, after the switch
+		assert_eq!(
+			parse_time_in("2024-03-12 08:00", NOW, &Dst),
+			Some(1710223200)
+		);
+		assert_eq!(format_time_in(1710223200, &Dst), "2024-03-12 08:00");
+		assert_eq!(format_time_in(NOW, &Dst), "2024-03-10 16:20");
+	}
+
+	#[test]
+	fn test_format_countdown() {
+		assert_eq!(format_countdown(45), "45s");
+		assert_eq!(format_countdown(125), "2m 05s");
+		assert_eq!(format_countdown(3900), "1h 05m");
+		assert_eq!(format_countdown(90000), "1d 1h");
+	}
+}